@@ -0,0 +1,193 @@
+use crate::contracts::{Contract, MIN_DELIVERY_DISTANCE};
+use crate::manouvers;
+use crate::prelude::*;
+use crate::sim::Touch;
+
+const DIRECTIONS: u32 = 32;
+const MAGNITUDES: u32 = 12;
+pub const CANDIDATES: u32 = DIRECTIONS * MAGNITUDES;
+
+// Each candidate is a full trajectory simulation, so the search is spread
+// across multiple frames
+const CANDIDATES_PER_FRAME: u32 = 4;
+
+pub struct Autopilot {
+    pub planet: usize,
+    pub dv: Option<Vec2>,
+    candidate: u32,
+    max_len: f32,
+}
+
+impl Autopilot {
+    fn new(planet: usize, game: &Game) -> Self {
+        Self {
+            planet,
+            dv: None,
+            candidate: 0,
+            max_len: manouvers::max_dv(game),
+        }
+    }
+
+    // Candidates are ordered by their length, so the first one that reaches
+    // the target is also the cheapest one
+    pub fn candidate(max_len: f32, idx: u32) -> Vec2 {
+        let len =
+            max_len * ((idx / DIRECTIONS + 1) as f32) / (MAGNITUDES as f32);
+
+        let angle =
+            2.0 * PI * ((idx % DIRECTIONS) as f32) / (DIRECTIONS as f32);
+
+        Vec2::from_angle(angle) * len
+    }
+}
+
+pub fn tic(
+    camera: &Camera,
+    game: &mut Game,
+    player: &mut Player,
+    planets: &[Planet],
+) {
+    // Suggestions are valid only for the moment they were computed for
+    if !game.is_paused() {
+        game.autopilot = None;
+        return;
+    }
+
+    if !game.manouver_mode && mouse_left_pressed() {
+        if let Some(contract) = hovered_cargo(game) {
+            if game.fuel - game.reserved_fuel() <= 0.00001 {
+                msgs::add("You don't have fuel.");
+            } else {
+                game.autopilot =
+                    Some(Autopilot::new(contract.dst_planet, game));
+            }
+        }
+    }
+
+    let Some(mut autopilot) = game.autopilot.take() else {
+        return;
+    };
+
+    let planet = &planets[autopilot.planet];
+
+    if autopilot.dv.is_none() {
+        if autopilot.candidate == 0
+            && reaches(
+                game,
+                &player.ship,
+                planets,
+                autopilot.planet,
+                Vec2::ZERO,
+            )
+        {
+            msgs::add("You're already on course!");
+            return;
+        }
+
+        for _ in 0..CANDIDATES_PER_FRAME {
+            if autopilot.candidate >= CANDIDATES {
+                msgs::add("Autopilot: destination is out of reach.");
+                return;
+            }
+
+            let dv =
+                Autopilot::candidate(autopilot.max_len, autopilot.candidate);
+
+            if reaches(game, &player.ship, planets, autopilot.planet, dv) {
+                autopilot.dv = Some(dv);
+                break;
+            }
+
+            autopilot.candidate += 1;
+        }
+    }
+
+    // ---
+
+    let planet_pos = camera.world_to_screen(planet.pos);
+
+    circb(planet_pos.x as i32, planet_pos.y as i32, 6, planet.color);
+
+    if autopilot.dv.is_some() {
+        Text::new("Autopilot found a burn.")
+            .at(vec2(0.0, 0.0))
+            .draw();
+
+        Text::new("Press Enter to confirm, X to cancel.")
+            .at(vec2(0.0, 8.0))
+            .draw();
+    } else {
+        let progress = 100 * autopilot.candidate / CANDIDATES;
+
+        Text::new(format!("Autopilot: searching... {}%", progress))
+            .at(vec2(0.0, 0.0))
+            .draw();
+
+        Text::new("Press X to cancel.").at(vec2(0.0, 8.0)).draw();
+    }
+
+    if key(keys::X) || mouse_right_pressed() {
+        return;
+    }
+
+    if let Some(dv) = autopilot.dv {
+        if keyp(keys::RETURN, 0, 0) {
+            if manouvers::burn(game, &mut player.ship, dv) {
+                game.speed = GameSpeed::Normal;
+            } else {
+                msgs::add("You don't have fuel.");
+            }
+
+            return;
+        }
+    }
+
+    game.autopilot = Some(autopilot);
+}
+
+fn reaches(
+    game: &Game,
+    ship: &Ship,
+    planets: &[Planet],
+    planet: usize,
+    dv: Vec2,
+) -> bool {
+    let mut ship = *ship;
+
+    ship.vel += dv;
+
+    let zone = planets[planet].radius + MIN_DELIVERY_DISTANCE;
+    let mut prev_tick = game.tick;
+    let mut prev_pos = ship.pos;
+
+    for step in sim::trajectory(game, &ship, planets, &game.manouver_nodes) {
+        let a = prev_pos - planets::position_at(planets, planet, prev_tick);
+
+        let b = step.pos - planets::position_at(planets, planet, step.tick);
+
+        if distance_to_segment(a, b) <= zone {
+            return true;
+        }
+
+        if step.touches == Some(Touch::Impact) {
+            return false;
+        }
+
+        prev_tick = step.tick;
+        prev_pos = step.pos;
+    }
+
+    false
+}
+
+pub fn hovered_cargo(game: &Game) -> Option<Contract> {
+    let mpos = mouse_pos();
+
+    if mpos.x < 16.0 || mpos.x >= 32.0 {
+        return None;
+    }
+
+    let idx = ((HEIGHT as f32 - mpos.y) / 16.0) as usize;
+
+    game.cargo_at_slot(idx).copied()
+}
@@ -0,0 +1,215 @@
+use crate::police::PoliceClass;
+use crate::prelude::*;
+
+const BOX_X: i32 = 30;
+const BOX_Y: i32 = 34;
+const BOX_W: i32 = 180;
+const BOX_H: i32 = 58;
+
+// Game stays paused until the negotiation is over
+pub struct Negotiation {
+    // Not shown to the player
+    demand: u32,
+    offer: u32,
+    can_flee: bool,
+}
+
+pub enum Outcome {
+    // Crew took the credits & the cargo from given slot, if any
+    Accepted { credits: u32, slot: Option<usize> },
+
+    Refused,
+
+    Fled,
+
+    Caught,
+}
+
+impl Negotiation {
+    // Crews from friendly planets ask for less
+    pub fn new(
+        rng: &mut dyn RngCore,
+        game: &Game,
+        class: PoliceClass,
+        reputation: f32,
+    ) -> Self {
+        let demand = rng.gen_range(class.bribe()) as f32;
+
+        Self {
+            demand: ((demand * (1.0 - 0.3 * reputation)).round() as u32).max(1),
+            offer: (game.credits / 2).max(1).min(game.credits),
+            can_flee: true,
+        }
+    }
+
+    pub fn tic(
+        &mut self,
+        rng: &mut dyn RngCore,
+        game: &Game,
+        wanted: f32,
+    ) -> Option<Outcome> {
+        let cargo = most_valuable_cargo(game);
+
+        if game.credits == 0 && cargo.is_none() && !self.can_flee {
+            return Some(Outcome::Caught);
+        }
+
+        if btnp(btns::LEFT, 16, 4) {
+            self.offer = self.offer.saturating_sub(1).max(1);
+        }
+
+        if btnp(btns::RIGHT, 16, 4) {
+            self.offer += 1;
+        }
+
+        self.offer = self.offer.min(game.credits);
+
+        let offer_chance = self.chance(self.offer, wanted);
+        let flee_chance = 0.5 - 0.3 * wanted;
+
+        let cargo_chance = cargo.map(|(_, value)| self.chance(value, wanted));
+
+        // ---
+
+        rect(BOX_X, BOX_Y, BOX_W, BOX_H, 0);
+        rectb(BOX_X, BOX_Y, BOX_W, BOX_H, 2);
+
+        Text::new("You're being boarded!")
+            .at(vec2(2.0 * (BOX_X + BOX_W / 2) as f32, (BOX_Y + 4) as f32))
+            .align_center()
+            .color(2)
+            .draw();
+
+        let options = [
+            (
+                game.credits > 0,
+                format!(
+                    "1 - Offer < ${}k > ({:.0}%)",
+                    self.offer,
+                    100.0 * offer_chance
+                ),
+            ),
+            (
+                cargo.is_some(),
+                match (cargo, cargo_chance) {
+                    (Some((_, value)), Some(chance)) => format!(
+                        "2 - Give ${}k cargo ({:.0}%)",
+                        value,
+                        100.0 * chance
+                    ),
+                    _ => "2 - Give cargo".to_string(),
+                },
+            ),
+            (
+                self.can_flee,
+                format!("3 - Try to flee ({:.0}%)", 100.0 * flee_chance),
+            ),
+        ];
+
+        let mpos = mouse_pos();
+        let mut selected = None;
+
+        for (idx, (enabled, text)) in options.into_iter().enumerate() {
+            let y = BOX_Y + 18 + 12 * idx as i32;
+
+            let is_hovered = enabled
+                && mpos.x >= BOX_X as f32
+                && mpos.x < (BOX_X + BOX_W) as f32
+                && mpos.y >= (y - 2) as f32
+                && mpos.y < (y + 8) as f32;
+
+            let color = if !enabled {
+                14
+            } else if is_hovered {
+                4
+            } else {
+                12
+            };
+
+            Text::new(text)
+                .at(vec2((BOX_X + 6) as f32, y as f32))
+                .color(color)
+                .draw();
+
+            let key = [keys::DIGIT_1, keys::DIGIT_2, keys::DIGIT_3][idx];
+
+            if enabled
+                && (keyp(key, 0, 0) || (is_hovered && mouse_left_pressed()))
+            {
+                selected = Some(idx);
+            }
+        }
+
+        Text::new("Use arrows to change the offer.")
+            .at(vec2(2.0 * (BOX_X + BOX_W / 2) as f32, (BOX_Y + 52) as f32))
+            .align_center()
+            .color(14)
+            .small()
+            .draw();
+
+        // ---
+
+        match selected? {
+            0 => Some(if rng.gen::<f32>() < offer_chance {
+                Outcome::Accepted {
+                    credits: self.offer,
+                    slot: None,
+                }
+            } else {
+                Outcome::Refused
+            }),
+
+            1 => {
+                let (slot, _) = cargo?;
+
+                Some(if rng.gen::<f32>() < cargo_chance? {
+                    Outcome::Accepted {
+                        credits: 0,
+                        slot: Some(slot),
+                    }
+                } else {
+                    Outcome::Refused
+                })
+            }
+
+            _ => {
+                if rng.gen::<f32>() < flee_chance {
+                    return Some(Outcome::Fled);
+                }
+
+                msgs::add("You didn't manage to flee!");
+
+                // Trying to outrun the police doesn't improve their mood
+                self.can_flee = false;
+                self.demand += 5;
+
+                None
+            }
+        }
+    }
+
+    fn chance(&self, value: u32, wanted: f32) -> f32 {
+        let ratio = value as f32 / self.demand as f32;
+
+        (ratio.min(1.0).powi(2) * (1.0 - 0.4 * wanted) + (ratio - 1.0).max(0.0))
+            .min(1.0)
+    }
+}
+
+// Returns slot & value of the most valuable cargo
+fn most_valuable_cargo(game: &Game) -> Option<(usize, u32)> {
+    game.cargo_hold
+        .iter()
+        .enumerate()
+        .map(|(slot, contract)| {
+            // Contraband is worth more to a crooked cop
+            let value = if contract.cargo.is_contraband() {
+                2 * contract.reward
+            } else {
+                contract.reward
+            };
+
+            (slot, value)
+        })
+        .max_by_key(|(_, value)| *value)
+}
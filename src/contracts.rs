@@ -1,13 +1,24 @@
+use crate::customs;
+use crate::game::{CARGO_HOLD_SLOTS, TICKS_PER_DAY};
+use crate::hunters::HunterState;
+use crate::market::Market;
 use crate::police::PoliceState;
 use crate::prelude::sprites::buttons;
 use crate::prelude::*;
+use crate::reputation::Reputation;
 
 pub const MIN_ACCEPT_DISTANCE: f32 = 256.0;
 pub const MIN_DELIVERY_DISTANCE: f32 = 256.0;
 
-pub const MIN_DELAY_BETWEEN_NEW_CONTRACTS: f32 = 5000.0;
+pub const MIN_DELAY_BETWEEN_NEW_CONTRACTS: u32 = 5 * TICKS_PER_SECOND;
 pub const MAX_CONTRACTS: usize = 3;
 
+// Contracts paying at least this much put bounty hunters on the player
+pub const MIN_BOUNTY_REWARD: u32 = 8;
+
+// How long after the deadline cargo can still be delivered, for less
+pub const LATE_DELIVERY_TICKS: u32 = 5 * TICKS_PER_DAY;
+
 #[derive(Clone, Copy)]
 pub struct Contract {
     pub src_planet: usize,
@@ -15,26 +26,138 @@ pub struct Contract {
     pub cargo: Cargo,
     pub reward: u32,
     pub wanted: f32,
-    pub expires_at: f32,
+    pub expires_at: u32,
+
+    // Set when the contract is accepted
+    pub loaded_at: u32,
+    pub deliver_by: u32,
 }
 
-#[derive(Clone, Copy)]
-pub enum Cargo {
-    Passengers,
-    Crabs,
-    Bananas,
+impl Contract {
+    // Late deliveries get paid less (down to a half), perishable cargo loses
+    // value as it ages
+    pub fn reward_at(&self, tick: u32) -> u32 {
+        let late = tick.saturating_sub(self.deliver_by) as f32
+            / LATE_DELIVERY_TICKS as f32;
+
+        let freshness = match self.cargo.ty().shelf_life {
+            Some(days) => {
+                let age = tick.saturating_sub(self.loaded_at) as f32
+                    / (days * TICKS_PER_DAY) as f32;
+
+                (1.0 - age).max(0.0)
+            }
+            None => 1.0,
+        };
+
+        ((self.reward as f32) * (1.0 - 0.5 * late.min(1.0)) * freshness).round()
+            as u32
+    }
+
+    pub fn has_failed(&self, tick: u32) -> bool {
+        tick > self.deliver_by + LATE_DELIVERY_TICKS
+    }
+}
+
+fn delivery_ticks(src: &Planet, dst: &Planet) -> u32 {
+    let days = 8.0 + src.pos.distance(dst.pos) / 1000.0;
+
+    (days * TICKS_PER_DAY as f32) as u32
+}
+
+pub struct CargoType {
+    pub name: &'static str,
+    pub sprite: u32,
+
+    // Local markets adjust it according to their supply & demand
+    pub base_price: u32,
+
+    // Relative to other types
+    pub frequency: f32,
+
+    pub mass: f32,
+
+    // In days; `None` if the cargo doesn't go bad
+    pub shelf_life: Option<u32>,
+
+    pub illegal_at: &'static [usize],
+
+    // In cargo hold slots
+    pub size: usize,
 }
 
+pub const CARGO_TYPES: &[CargoType] = &[
+    CargoType {
+        name: "Passengers",
+        sprite: 352,
+        base_price: 3,
+        frequency: 0.425,
+        mass: 1.0,
+        shelf_life: None,
+        illegal_at: &[],
+        size: 1,
+    },
+    CargoType {
+        name: "Crabs",
+        sprite: 356,
+        base_price: 6,
+        frequency: 0.15,
+        mass: 2.0,
+        shelf_life: Some(15),
+        illegal_at: &[2, 3, 4, 6, 9],
+        size: 1,
+    },
+    CargoType {
+        name: "Bananas",
+        sprite: 354,
+        base_price: 2,
+        frequency: 0.425,
+        mass: 4.0,
+        shelf_life: Some(25),
+        illegal_at: &[],
+        size: 2,
+    },
+];
+
+// Index into CARGO_TYPES
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Cargo(usize);
+
 impl Cargo {
-    pub fn sprite(&self) -> u32 {
-        match self {
-            Cargo::Passengers => 352,
-            Cargo::Bananas => 354,
-            Cargo::Crabs => 356,
-        }
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..CARGO_TYPES.len()).map(Cargo)
+    }
+
+    pub fn rand(rng: &mut dyn RngCore) -> Self {
+        let total: f32 = CARGO_TYPES.iter().map(|ty| ty.frequency).sum();
+        let mut roll = rng.gen::<f32>() * total;
+
+        Self::all()
+            .find(|cargo| {
+                roll -= cargo.ty().frequency;
+                roll < 0.0
+            })
+            .unwrap_or(Cargo(0))
+    }
+
+    pub fn id(self) -> usize {
+        self.0
+    }
+
+    pub fn ty(self) -> &'static CargoType {
+        &CARGO_TYPES[self.0]
+    }
+
+    pub fn is_contraband(self) -> bool {
+        !self.ty().illegal_at.is_empty()
+    }
+
+    pub fn is_contraband_at(self, planet: usize) -> bool {
+        self.ty().illegal_at.contains(&planet)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn tic(
     rng: &mut dyn RngCore,
     camera: &mut Camera,
@@ -42,13 +165,16 @@ pub fn tic(
     player: &Player,
     planets: &[Planet],
     police: &mut PoliceState,
+    hunters: &mut HunterState,
+    market: &mut Market,
+    reputation: &mut Reputation,
 ) {
     let mo = mouse();
 
     // Spawn new contracts
     #[allow(clippy::collapsible_if)]
     if game.contracts.len() < MAX_CONTRACTS {
-        if game.time - game.time_of_last_contract_spawned
+        if game.tick - game.tick_of_last_contract_spawned
             > MIN_DELAY_BETWEEN_NEW_CONTRACTS
         {
             if rng.gen::<f32>() > 0.5 {
@@ -72,33 +198,45 @@ pub fn tic(
                         continue;
                     }
 
-                    let reward;
-                    let wanted;
-                    let cargo;
+                    // Planets that got betrayed too many times don't deal with
+                    // the player anymore
+                    if reputation.is_hostile(src_planet) {
+                        continue;
+                    }
 
-                    if rng.gen_bool(0.15) {
-                        reward = rng.gen_range(8..=12);
-                        wanted = 0.7;
-                        cargo = Cargo::Crabs;
-                    } else {
-                        reward = rng.gen_range(1..=5);
-                        wanted = ((reward as f32) / 5.0 * 0.3).clamp(0.1, 0.33);
-
-                        cargo = if rng.gen_bool(0.5) {
-                            Cargo::Passengers
-                        } else {
-                            Cargo::Bananas
-                        };
+                    let cargo = Cargo::rand(rng);
+
+                    // Nobody pays for hauling cargo to where it's cheaper
+                    let reward =
+                        market.reward(src_planet, dst_planet, cargo) as f32;
+
+                    let reward = (reward * reputation.reward_factor(dst_planet))
+                        .round() as u32;
+
+                    if reward == 0 {
+                        continue;
                     }
 
+                    let wanted = if cargo.is_contraband_at(src_planet)
+                        || cargo.is_contraband_at(dst_planet)
+                    {
+                        0.7
+                    } else {
+                        ((reward as f32) / 5.0 * 0.3).clamp(0.1, 0.33)
+                    };
+
                     game.contracts.push(Contract {
                         src_planet,
                         dst_planet,
                         cargo,
                         reward,
                         wanted,
-                        expires_at: game.time
-                            + rng.gen_range(20.0..45.0) * 1000.0,
+                        expires_at: game.tick
+                            + rng.gen_range(
+                                20 * TICKS_PER_SECOND..45 * TICKS_PER_SECOND,
+                            ),
+                        loaded_at: 0,
+                        deliver_by: 0,
                     });
 
                     spawned = true;
@@ -108,16 +246,17 @@ pub fn tic(
                 if spawned {
                     audio::play(sounds::NEW_CONTRACT);
                     msgs::add("New contract available!");
+                    game.drop_warp();
                 }
 
-                game.time_of_last_contract_spawned = game.time;
+                game.tick_of_last_contract_spawned = game.tick;
             }
         }
     }
 
     // Remove contracts that went stale
     game.contracts
-        .extract_if(|contract| game.time >= contract.expires_at)
+        .extract_if(|contract| game.tick >= contract.expires_at)
         .for_each(drop);
 
     // Draw available unselected contracts
@@ -146,7 +285,7 @@ pub fn tic(
         if ship_to_planet_distance < src_planet.radius + MIN_ACCEPT_DISTANCE
             && !camera.is_animating()
             && !game.is_paused()
-            && game.cargo_hold.iter().any(|c| c.is_none())
+            && game.free_hold_slots() >= contract.cargo.ty().size
         {
             game.selected_contract = Some(idx);
             game.manouver_mode = false;
@@ -192,7 +331,7 @@ pub fn tic(
 
     // Draw selected contract ui
     if let Some(selected_contract) = game.selected_contract {
-        let contract = &game.contracts[selected_contract];
+        let contract = game.contracts[selected_contract];
 
         let src_planet = &planets[contract.src_planet];
         let dst_planet = &planets[contract.dst_planet];
@@ -207,9 +346,15 @@ pub fn tic(
         let mpos = vec2(mo.x as f32, mo.y as f32);
         let tooltip_pos = (src_pos + dst_pos) * 0.5 + vec2(0.0, -14.0);
 
-        let txt_width = Text::new(format!("+${}k - Accept?", contract.reward))
-            .at(tooltip_pos)
-            .draw() as f32
+        let delivery_days =
+            delivery_ticks(src_planet, dst_planet) / TICKS_PER_DAY;
+
+        let txt_width = Text::new(format!(
+            "+${}k in {}d - Accept?",
+            contract.reward, delivery_days
+        ))
+        .at(tooltip_pos)
+        .draw() as f32
             + 1.0;
 
         let btn_accept_pos = tooltip_pos + vec2(txt_width - 3.0 * 8.0, 14.0);
@@ -245,16 +390,35 @@ pub fn tic(
 
                 camera.animate_back();
 
-                for hold in &mut game.cargo_hold {
-                    if hold.is_none() {
-                        *hold = Some(*contract);
-                        break;
-                    }
-                }
+                game.cargo_hold.push(Contract {
+                    loaded_at: game.tick,
+                    deliver_by: game.tick
+                        + delivery_ticks(src_planet, dst_planet),
+                    ..contract
+                });
 
                 audio::play(sounds::COIN);
                 police.increment_wanted_level(contract.wanted);
+                market.pick_up(contract.src_planet, contract.cargo);
+
+                if contract.reward >= MIN_BOUNTY_REWARD {
+                    hunters.add_bounty(rng, game, contract.reward);
+                }
+
                 game.contracts.remove(selected_contract);
+
+                if src_planet.customs {
+                    let slot = game.cargo_hold.len() - 1;
+
+                    customs::inspect(
+                        rng,
+                        game,
+                        police,
+                        reputation,
+                        contract.src_planet,
+                        slot,
+                    );
+                }
             } else if btn_reject_hover {
                 game.selected_contract = None;
                 game.speed = GameSpeed::Normal;
@@ -268,7 +432,7 @@ pub fn tic(
     // If mouse is over cargo hold, show arrows to destinations
     let mpos = vec2(mo.x as f32, mo.y as f32);
 
-    let cargo_hold_height = 3.0 * 16.0;
+    let cargo_hold_height = CARGO_HOLD_SLOTS as f32 * 16.0;
 
     let cargo_hold_bounds = (
         vec2(16.0, HEIGHT as f32 - cargo_hold_height),
@@ -280,7 +444,7 @@ pub fn tic(
         && mpos.y > cargo_hold_bounds.0.y
         && mpos.y < cargo_hold_bounds.0.y + cargo_hold_bounds.1.y
     {
-        for contract in game.cargo_hold.iter().flatten() {
+        for contract in &game.cargo_hold {
             let dst_planet = &planets[contract.dst_planet];
             let dst_pos = camera.world_to_screen(dst_planet.pos);
 
@@ -290,31 +454,72 @@ pub fn tic(
         }
     }
 
+    // Failed deliveries
+    let failed: Vec<_> = game
+        .cargo_hold
+        .extract_if(|contract| contract.has_failed(game.tick))
+        .collect();
+
+    for contract in failed {
+        reputation.change(contract.dst_planet, -0.2);
+        police.increment_wanted_level(0.2);
+        msgs::add("Contract failed - the cargo is way past its deadline!");
+    }
+
     // Deliveries
-    let mut deliveries_to_clear = vec![];
+    let mut idx = 0;
 
-    for (idx, contract) in game.cargo_hold.iter().enumerate() {
-        if let Some(contract) = contract {
-            let dst_planet = &planets[contract.dst_planet];
+    while idx < game.cargo_hold.len() {
+        let contract = game.cargo_hold[idx];
 
-            let ship_to_planet_distance =
-                (player.ship.pos - dst_planet.pos).length();
+        let dst_planet = &planets[contract.dst_planet];
 
-            if ship_to_planet_distance
-                < dst_planet.radius + MIN_DELIVERY_DISTANCE
+        let ship_to_planet_distance =
+            (player.ship.pos - dst_planet.pos).length();
+
+        if ship_to_planet_distance < dst_planet.radius + MIN_DELIVERY_DISTANCE {
+            // Confiscated cargo doesn't get paid for
+            if dst_planet.customs
+                && customs::inspect(
+                    rng,
+                    game,
+                    police,
+                    reputation,
+                    contract.dst_planet,
+                    idx,
+                )
             {
-                game.credits += contract.reward;
-                game.total_credits += contract.reward;
+                continue;
+            }
 
-                audio::play(sounds::COIN);
-                msgs::add("Delivery complete!");
+            let reward = contract.reward_at(game.tick);
+
+            market.deliver(contract.dst_planet, contract.cargo);
 
-                deliveries_to_clear.push(idx);
+            game.credits += reward;
+            game.total_credits += reward;
+
+            audio::play(sounds::COIN);
+
+            if reward == 0 {
+                reputation.change(contract.dst_planet, -0.1);
+
+                msgs::add(format!(
+                    "The {} went bad, nobody pays for that.",
+                    contract.cargo.ty().name.to_lowercase()
+                ));
+            } else if reward < contract.reward {
+                msgs::add(format!("Late delivery, paid only ${}k.", reward));
+            } else {
+                reputation.change(contract.src_planet, 0.05);
+                reputation.change(contract.dst_planet, 0.1);
+
+                msgs::add("Delivery complete!");
             }
-        }
-    }
 
-    for idx in deliveries_to_clear {
-        game.cargo_hold[idx] = None;
+            game.cargo_hold.remove(idx);
+        } else {
+            idx += 1;
+        }
     }
 }
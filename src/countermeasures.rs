@@ -0,0 +1,238 @@
+use crate::police::PoliceState;
+use crate::prelude::*;
+use crate::sim::FramePositions;
+
+const MAX_CHARGES: u32 = 3;
+const DECOY_PRICE: u32 = 3;
+const JAMMER_PRICE: u32 = 5;
+
+const DECOY_TICKS: u32 = 15 * TICKS_PER_SECOND;
+
+const JAMMER_TICKS: u32 = 10 * TICKS_PER_SECOND;
+
+// Sold at the same planets as fuel
+const SHOP_PLANETS: &[usize] = &[7, 9];
+const MIN_SHOP_DISTANCE: f32 = 250.0;
+
+struct Decoy {
+    ship: Ship,
+    expires_at: u32,
+}
+
+static mut DECOY: Option<Decoy> = None;
+
+pub fn decoy() -> Option<Ship> {
+    unsafe { DECOY.as_ref().map(|decoy| decoy.ship) }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Countermeasure {
+    Decoy,
+    Jammer,
+}
+
+impl Countermeasure {
+    pub const ALL: [Self; 2] = [Countermeasure::Decoy, Countermeasure::Jammer];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Countermeasure::Decoy => "Decoy",
+            Countermeasure::Jammer => "Jammer",
+        }
+    }
+
+    pub fn hotkey(self) -> (i32, &'static str) {
+        match self {
+            Countermeasure::Decoy => (keys::Q, "Q"),
+            Countermeasure::Jammer => (keys::E, "E"),
+        }
+    }
+
+    fn price(self) -> u32 {
+        match self {
+            Countermeasure::Decoy => DECOY_PRICE,
+            Countermeasure::Jammer => JAMMER_PRICE,
+        }
+    }
+
+    pub fn charges(self, game: &Game) -> u32 {
+        match self {
+            Countermeasure::Decoy => game.decoys,
+            Countermeasure::Jammer => game.jammers,
+        }
+    }
+
+    fn charges_mut(self, game: &mut Game) -> &mut u32 {
+        match self {
+            Countermeasure::Decoy => &mut game.decoys,
+            Countermeasure::Jammer => &mut game.jammers,
+        }
+    }
+}
+
+pub fn tic(
+    rng: &mut dyn RngCore,
+    camera: &Camera,
+    game: &mut Game,
+    player: &Player,
+    planets: &[Planet],
+    police: &mut PoliceState,
+) {
+    // -- Decoy --
+    let decoy = unsafe { &mut DECOY };
+
+    if let Some(active) = decoy {
+        let positions = FramePositions::new(game, planets);
+
+        for step in 0..game.steps() {
+            let tick = game.tick + step;
+
+            sim::eval_ship(
+                game.integrator,
+                &mut active.ship,
+                planets,
+                positions.at(tick),
+                positions.at(tick + 1),
+            );
+        }
+
+        if game.tick >= active.expires_at
+            || planets
+                .iter()
+                .any(|planet| planet.collides_with(active.ship.pos))
+        {
+            *decoy = None;
+        } else if blink() {
+            ShipSprite::player()
+                .at(camera.world_to_screen(active.ship.pos))
+                .rot(PI - active.ship.vel.angle_between(Vec2::Y))
+                .scale(camera.scale.max(0.3))
+                .draw(Some(game));
+        }
+    }
+
+    // -- Shopping & deploying --
+    let is_docked = SHOP_PLANETS.iter().any(|&planet_id| {
+        let planet = &planets[planet_id];
+
+        planet.pos.distance(player.ship.pos) < planet.radius + MIN_SHOP_DISTANCE
+    });
+
+    for countermeasure in Countermeasure::ALL {
+        let (hotkey, _) = countermeasure.hotkey();
+        let clicked = is_hovered(countermeasure) && mouse_left_pressed();
+
+        if is_docked && clicked {
+            buy(game, countermeasure);
+        } else if keyp(hotkey, 0, 0) || clicked {
+            deploy(rng, game, player, police, countermeasure);
+        }
+    }
+}
+
+fn buy(game: &mut Game, countermeasure: Countermeasure) {
+    let price = countermeasure.price();
+
+    if countermeasure.charges(game) >= MAX_CHARGES {
+        msgs::add(format!("{} is fully charged.", countermeasure.name()));
+    } else if game.credits < price {
+        msgs::add(format!("You can't afford a {}.", countermeasure.name()));
+    } else {
+        game.credits -= price;
+        *countermeasure.charges_mut(game) += 1;
+
+        audio::play(sounds::COIN);
+        msgs::add(format!("Bought a {}, $-{}k", countermeasure.name(), price));
+    }
+}
+
+fn deploy(
+    rng: &mut dyn RngCore,
+    game: &mut Game,
+    player: &Player,
+    police: &mut PoliceState,
+    countermeasure: Countermeasure,
+) {
+    if player.is_just_spawned || game.is_paused() {
+        return;
+    }
+
+    if countermeasure.charges(game) == 0 {
+        msgs::add(format!("No {} charges left.", countermeasure.name()));
+        return;
+    }
+
+    *countermeasure.charges_mut(game) -= 1;
+
+    match countermeasure {
+        Countermeasure::Decoy => {
+            let kick =
+                vec2(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0))
+                    .normalize_or_zero()
+                    * 0.5;
+
+            unsafe {
+                DECOY = Some(Decoy {
+                    ship: Ship {
+                        pos: player.ship.pos,
+                        vel: player.ship.vel + kick,
+                    },
+                    expires_at: game.tick + DECOY_TICKS,
+                });
+            }
+
+            for _ in 0..12 {
+                particles::spawn_exhaust(player.ship.pos, kick * 8.0);
+            }
+
+            msgs::add("Decoy deployed!");
+        }
+
+        Countermeasure::Jammer => {
+            police.jam(player, game.tick + JAMMER_TICKS);
+
+            for idx in 0..16 {
+                let dir = Vec2::from_angle(2.0 * PI * (idx as f32) / 16.0);
+
+                particles::spawn_exhaust(
+                    player.ship.pos + dir * 20.0,
+                    dir * 4.0,
+                );
+            }
+
+            msgs::add("Sensors jammed!");
+        }
+    }
+
+    sfx(
+        4,
+        SfxOptions {
+            note: 5,
+            octave: 4,
+            duration: 15,
+            volume_left: 8,
+            volume_right: 8,
+            ..Default::default()
+        },
+    );
+}
+
+// Countermeasure slots sit right next to the cargo hold
+pub fn slot_pos(countermeasure: Countermeasure) -> Vec2 {
+    let idx = match countermeasure {
+        Countermeasure::Decoy => 0.0,
+        Countermeasure::Jammer => 1.0,
+    };
+
+    vec2(32.0, HEIGHT as f32 - 16.0 * (idx + 1.0))
+}
+
+pub fn is_hovered(countermeasure: Countermeasure) -> bool {
+    let pos = slot_pos(countermeasure);
+    let mpos = mouse_pos();
+
+    mpos.x >= pos.x
+        && mpos.x < pos.x + 16.0
+        && mpos.y >= pos.y
+        && mpos.y < pos.y + 16.0
+}
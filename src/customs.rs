@@ -0,0 +1,64 @@
+use crate::police::PoliceState;
+use crate::prelude::*;
+use crate::reputation::Reputation;
+
+// Returns whether the cargo at given slot got confiscated
+pub fn inspect(
+    rng: &mut dyn RngCore,
+    game: &mut Game,
+    police: &mut PoliceState,
+    reputation: &mut Reputation,
+    planet: usize,
+    slot: usize,
+) -> bool {
+    let is_contraband = game.cargo_hold[slot].cargo.is_contraband_at(planet);
+
+    let chance = if is_contraband { 0.5 } else { 0.2 };
+    // Planets that like the player keep their police off the player's back
+    let chance = (chance + 0.4 * police.wanted() - 0.2 * reputation.of(planet))
+        .clamp(0.05, 0.9);
+
+    if !rng.gen_bool(chance as f64) {
+        return false;
+    }
+
+    sfx(
+        1,
+        SfxOptions {
+            note: 1,
+            octave: 2,
+            duration: 5,
+            channel: 0,
+            ..Default::default()
+        },
+    );
+
+    if !is_contraband {
+        msgs::add("Customs scan: you're clean, move along.");
+        return false;
+    }
+
+    // The more the police wants you, the less likely it is to end with just a
+    // fine
+    let roll = rng.gen::<f32>() - 0.3 * police.wanted();
+
+    if roll > 0.5 && game.credits > 0 {
+        let fine = rng.gen_range(5..=15).min(game.credits);
+
+        game.credits -= fine;
+        reputation.change(planet, -0.1);
+        msgs::add(format!("Customs fined you for contraband, $-{}k", fine));
+    } else if roll > 0.2 {
+        game.cargo_hold.remove(slot);
+        reputation.change(planet, -0.2);
+        msgs::add("Customs confiscated your cargo!");
+
+        return true;
+    } else {
+        police.alert(0.5);
+        reputation.change(planet, -0.3);
+        msgs::add("Contraband found - customs alerted the police!");
+    }
+
+    false
+}
@@ -1,5 +1,95 @@
 use crate::prelude::*;
+use crate::GameOverReason;
 
-pub fn tic(player: &Player, planets: &[Planet]) -> bool {
-    planets[0].collides_with(player.ship.pos)
+const MIN_LANDING_RADIUS: f32 = 90.0;
+
+// Relative to the planet
+const MAX_LANDING_SPEED: f32 = 0.5;
+
+// Landed ship can sink this deep due to rounding errors
+const SURFACE_TOLERANCE: f32 = 1.0;
+
+pub fn tic(
+    game: &Game,
+    player: &mut Player,
+    planets: &[Planet],
+) -> Option<GameOverReason> {
+    // Landed ship is held on the surface by `sim::tic()`
+    if player.landed_on.is_some() {
+        return None;
+    }
+
+    let mut landed_on = None;
+
+    for (planet_id, planet) in planets.iter().enumerate() {
+        if !planet.collides_with(player.ship.pos) {
+            continue;
+        }
+
+        if planet_id == 0 {
+            return Some(GameOverReason::FellIntoTheSun);
+        }
+
+        let planet_vel = planets::velocity_at(planets, planet_id, game.tick);
+        let rel_vel = player.ship.vel - planet_vel;
+        let normal = (player.ship.pos - planet.pos).normalize_or_zero();
+
+        // Ship is already on its way out (e.g. just after taking off); one
+        // that's below the surface has crashed, wherever it's heading
+        let on_surface = player.ship.pos.distance(planet.pos)
+            >= planet.radius - SURFACE_TOLERANCE;
+
+        if on_surface && rel_vel.dot(normal) >= 0.0 {
+            continue;
+        }
+
+        if planet.radius >= MIN_LANDING_RADIUS
+            && rel_vel.length() <= MAX_LANDING_SPEED
+        {
+            player.ship.pos = planet.pos + normal * planet.radius;
+            player.ship.vel = planet_vel;
+
+            landed_on = Some(planet_id);
+        } else {
+            return Some(GameOverReason::CrashedIntoPlanet);
+        }
+    }
+
+    if landed_on.is_some() && player.landed_on.is_none() {
+        msgs::add("Touchdown!");
+    }
+
+    player.landed_on = landed_on;
+
+    None
+}
+
+// Returns whether the player has burnt away from the surface (or along it)
+pub fn is_taking_off(
+    ship: &Ship,
+    planets: &[Planet],
+    planet_id: usize,
+    tick: u32,
+) -> bool {
+    let planet_pos = planets::position_at(planets, planet_id, tick);
+    let planet_vel = planets::velocity_at(planets, planet_id, tick);
+    let rel_vel = ship.vel - planet_vel;
+    let normal = (ship.pos - planet_pos).normalize_or_zero();
+
+    rel_vel.dot(normal) > 0.0
+        || rel_vel.reject_from_normalized(normal).length() > MAX_LANDING_SPEED
+}
+
+// Gravity alone only ever pushes the landed ship into the planet
+pub fn hold_on_surface(
+    ship: &mut Ship,
+    planets: &[Planet],
+    planet_id: usize,
+    tick: u32,
+) {
+    let planet_pos = planets::position_at(planets, planet_id, tick);
+    let normal = (ship.pos - planet_pos).normalize_or_zero();
+
+    ship.pos = planet_pos + normal * planets[planet_id].radius;
+    ship.vel = planets::velocity_at(planets, planet_id, tick);
 }
@@ -1,8 +1,75 @@
+use crate::autopilot::{self, Autopilot};
+use crate::contracts::MIN_DELIVERY_DISTANCE;
+use crate::game::TICKS_PER_DAY;
+use crate::manouvers;
 use crate::prelude::*;
+use crate::sim::{Touch, Trajectory, TRAJECTORY_STEPS};
 
 const MIN_REFUEL_DISTANCE: f32 = 250.0;
 const REFUELLING_PLANETS: &[usize] = &[7, 9];
 
+// Reachability is estimated by sampling burns in the background, with the
+// simulation spread across frames
+const STEPS_PER_FRAME: u32 = TRAJECTORY_STEPS / 4;
+
+// Every fifth of the autopilot's burns - since the number of directions it
+// tries is a power of two, the sample still covers all of them
+const SAMPLE_STRIDE: u32 = 5;
+const SAMPLES: u32 = autopilot::CANDIDATES.div_ceil(SAMPLE_STRIDE);
+
+// Once finished, the estimate is kept until the ship has moved on for a day
+const RECOMPUTE_TICKS: u32 = TICKS_PER_DAY;
+
+// Planets beyond it even at the top speed are reported as unknown
+const MAX_HORIZON: u32 = 3000;
+
+struct Reachability {
+    key: Option<ReachabilityKey>,
+
+    // All the candidates start from here, so that they're comparable
+    origin: (u32, Ship),
+
+    horizon: u32,
+    max_dv: f32,
+    max_speed: f32,
+    candidate: u32,
+    finished: bool,
+    trajectory: Option<(Trajectory, u32, Vec2)>,
+    pending: Vec<bool>,
+    reachable: Vec<Option<bool>>,
+}
+
+#[derive(PartialEq)]
+struct ReachabilityKey {
+    fuel: f32,
+    cargo_mass: f32,
+    nodes: Vec<(u32, Vec2)>,
+}
+
+static mut REACHABILITY: Reachability = Reachability {
+    key: None,
+    origin: (
+        0,
+        Ship {
+            pos: Vec2::ZERO,
+            vel: Vec2::ZERO,
+        },
+    ),
+    horizon: 0,
+    max_dv: 0.0,
+    max_speed: 0.0,
+    candidate: 0,
+    finished: false,
+    trajectory: None,
+    pending: Vec::new(),
+    reachable: Vec::new(),
+};
+
+// Returns `None` if that's not known (yet)
+pub fn is_reachable(planet: usize) -> Option<bool> {
+    unsafe { REACHABILITY.reachable.get(planet).copied().flatten() }
+}
+
 pub fn tic(
     camera: &Camera,
     game: &mut Game,
@@ -13,6 +80,8 @@ pub fn tic(
     let mpos = vec2(mo.x as f32, mo.y as f32);
     let ui_visible = game.selected_contract.is_none() && !camera.is_animating();
 
+    estimate_reachability(game, player, planets);
+
     let fuel_gauge_height = 48.0;
 
     let fuel_gauge_bounds = (
@@ -26,16 +95,38 @@ pub fn tic(
         && mpos.y > fuel_gauge_bounds.0.y
         && mpos.y < fuel_gauge_bounds.0.y + fuel_gauge_bounds.1.y
     {
-        for planet in REFUELLING_PLANETS {
-            let planet = &planets[*planet];
+        for &planet_id in REFUELLING_PLANETS {
+            let planet = &planets[planet_id];
             let planet_pos = camera.world_to_screen(planet.pos);
 
-            Arrow::new(mpos, planet_pos, planet.color)
-                .margin(5.0)
-                .draw();
+            if is_reachable(planet_id) == Some(false) {
+                Arrow::new(mpos, planet_pos, 2).margin(5.0).draw();
+
+                Text::new("out of reach")
+                    .at(planet_pos + vec2(6.0, -2.0))
+                    .color(2)
+                    .small()
+                    .draw();
+            } else {
+                Arrow::new(mpos, planet_pos, planet.color)
+                    .margin(5.0)
+                    .draw();
+            }
         }
     }
 
+    let is_stranded = REFUELLING_PLANETS
+        .iter()
+        .all(|&planet_id| is_reachable(planet_id) == Some(false));
+
+    if ui_visible && is_stranded && game.fuel < 1.0 && blink() {
+        Text::new("STRANDED")
+            .at(vec2(0.0, fuel_gauge_bounds.0.y - 7.0))
+            .color(2)
+            .small()
+            .draw();
+    }
+
     // Refuel
     if game.fuel >= 1.0 {
         return;
@@ -55,3 +146,171 @@ pub fn tic(
         }
     }
 }
+
+fn estimate_reachability(game: &Game, player: &Player, planets: &[Planet]) {
+    let state = unsafe { &mut REACHABILITY };
+
+    let key = ReachabilityKey {
+        fuel: game.fuel,
+        cargo_mass: game.cargo_mass(),
+        nodes: game
+            .manouver_nodes
+            .iter()
+            .map(|node| (node.tick, node.dv))
+            .collect(),
+    };
+
+    // Burns, refuelling and picking up cargo change what can be reached, so
+    // the previous estimate is no longer valid; the ship merely moving along
+    // keeps it good enough until a fresh one is ready
+    if state.key.as_ref() != Some(&key) {
+        state.key = Some(key);
+        state.reachable.clear();
+        restart(state, game, player, planets);
+    } else if state.finished
+        && game.tick / RECOMPUTE_TICKS != state.origin.0 / RECOMPUTE_TICKS
+    {
+        restart(state, game, player, planets);
+    }
+
+    let (origin_tick, origin_ship) = state.origin;
+    let mut budget = STEPS_PER_FRAME;
+
+    while budget > 0 && !state.finished {
+        if state.trajectory.is_none() {
+            // Candidate zero is "no burn at all", the rest are a sample of
+            // the burns the autopilot would try
+            let dv = if state.candidate == 0 {
+                Vec2::ZERO
+            } else {
+                Autopilot::candidate(
+                    state.max_dv,
+                    (state.candidate - 1) * SAMPLE_STRIDE,
+                )
+            };
+
+            let mut ship = origin_ship;
+
+            ship.vel += dv;
+
+            let trajectory = Trajectory::new(
+                game,
+                &ship,
+                &game.manouver_nodes,
+                state.horizon,
+            )
+            .starting_at(origin_tick);
+
+            state.trajectory = Some((trajectory, origin_tick, ship.pos));
+        }
+
+        let Some((trajectory, prev_tick, prev_pos)) = &mut state.trajectory
+        else {
+            break;
+        };
+
+        let steps = trajectory.steps();
+        let completed = !mark_reached(
+            trajectory,
+            planets,
+            prev_tick,
+            prev_pos,
+            &mut state.pending,
+        );
+
+        budget = budget.saturating_sub(trajectory.steps() - steps + 1);
+
+        if !completed {
+            continue;
+        }
+
+        state.trajectory = None;
+        state.candidate += 1;
+
+        if state.candidate > SAMPLES || state.max_dv == 0.0 {
+            // Planets that couldn't be reached within the horizon even at
+            // the top speed are beyond what the estimate can tell
+            let reach = state.max_speed * ticks_to_time(state.horizon);
+
+            state.reachable = state
+                .pending
+                .iter()
+                .enumerate()
+                .map(|(planet_id, &reached)| {
+                    let dist = planets[planet_id].pos.distance(origin_ship.pos);
+
+                    if reached {
+                        Some(true)
+                    } else if dist > reach {
+                        None
+                    } else {
+                        Some(false)
+                    }
+                })
+                .collect();
+
+            state.finished = true;
+        }
+    }
+}
+
+fn restart(
+    state: &mut Reachability,
+    game: &Game,
+    player: &Player,
+    planets: &[Planet],
+) {
+    let ship = player.ship;
+    let max_dv = manouvers::max_dv(game);
+
+    // Trajectories should be long enough to get to the farthest planet,
+    // allowing for a curved path
+    let max_speed = (ship.vel.length() + max_dv).max(0.01);
+
+    let max_dist = planets
+        .iter()
+        .map(|planet| planet.pos.distance(ship.pos))
+        .fold(0.0, f32::max);
+
+    state.origin = (game.tick, ship);
+    state.max_dv = max_dv;
+    state.max_speed = max_speed;
+    state.horizon = ((2.0 * max_dist / max_speed / DT) as u32)
+        .clamp(TRAJECTORY_STEPS, MAX_HORIZON);
+    state.candidate = 0;
+    state.finished = false;
+    state.trajectory = None;
+    state.pending = vec![false; planets.len()];
+}
+
+// Returns `false` once the trajectory is over
+fn mark_reached(
+    trajectory: &mut Trajectory,
+    planets: &[Planet],
+    prev_tick: &mut u32,
+    prev_pos: &mut Vec2,
+    reached: &mut [bool],
+) -> bool {
+    let Some(step) = trajectory.next(planets) else {
+        return false;
+    };
+
+    for (planet_id, planet) in planets.iter().enumerate() {
+        if reached[planet_id] {
+            continue;
+        }
+
+        let a =
+            *prev_pos - planets::position_at(planets, planet_id, *prev_tick);
+        let b = step.pos - planets::position_at(planets, planet_id, step.tick);
+
+        if distance_to_segment(a, b) <= planet.radius + MIN_DELIVERY_DISTANCE {
+            reached[planet_id] = true;
+        }
+    }
+
+    *prev_tick = step.tick;
+    *prev_pos = step.pos;
+
+    step.touches != Some(Touch::Impact)
+}
@@ -1,5 +1,9 @@
+use crate::autopilot::Autopilot;
 use crate::contracts::Contract;
+use crate::manouvers::{self, ManouverNode};
+use crate::police::Difficulty;
 use crate::prelude::*;
+use crate::sim::Integrator;
 
 static mut GAME: Option<Game> = None;
 
@@ -14,58 +18,154 @@ pub unsafe fn get_mut() -> &'static mut Game {
 pub fn tic() {
     let game = unsafe { get_mut() };
 
-    game.time += DT * game.speed.delta();
+    if game.warp_drop_requested {
+        game.warp_drop_requested = false;
+
+        if game.speed.is_warp() {
+            game.speed = GameSpeed::Normal;
+            msgs::add("Dropping out of warp.");
+        }
+    }
+
+    game.tick += game.steps();
+
+    // Switching integrators mid-game is useful only for playtesting
+    if cfg!(feature = "debug") && keyp(keys::I, 0, 0) {
+        game.integrator = game.integrator.next();
+        msgs::add(format!("Integrator: {}", game.integrator.name()));
+    }
 }
 
+pub const TICKS_PER_DAY: u32 = 150;
+
+const WARP_DROP_DISTANCE: f32 = 2500.0;
+pub const CARGO_HOLD_SLOTS: usize = 3;
+
 pub struct Game {
-    pub time: f32,
+    // All the timers are expressed in ticks, so that they compare exactly
+    pub tick: u32,
+
     pub speed: GameSpeed,
+    pub warp_drop_requested: bool,
+    pub integrator: Integrator,
+    pub difficulty: Difficulty,
     pub fuel: f32,
     pub credits: u32,
     pub total_credits: u32,
 
+    // Countermeasure charges
+    pub decoys: u32,
+    pub jammers: u32,
+
     // Manouver mode stuff
     pub manouver_mode: bool,
     pub manouver_dv: Vec2,
     pub manouver_fuel: f32,
+    pub manouver_planner: bool,
+
+    // Tick & position of the node that's being planned (`None` if the
+    // manouver is being made at the ship)
+    pub manouver_node: Option<(u32, Vec2)>,
+    pub manouver_nodes: Vec<ManouverNode>,
+    pub autopilot: Option<Autopilot>,
 
     // Contracts stuff
     pub contracts: Vec<Contract>,
     pub selected_contract: Option<usize>,
-    pub cargo_hold: [Option<Contract>; 3],
-    pub time_of_last_contract_spawned: f32,
+
+    // Cargo hold has `CARGO_HOLD_SLOTS` slots, each contract taking as many of
+    // them as its cargo's size
+    pub cargo_hold: Vec<Contract>,
+    pub tick_of_last_contract_spawned: u32,
 }
 
 impl Game {
     pub fn init() -> Self {
         Self {
-            time: 0.0,
+            tick: 0,
             speed: GameSpeed::Paused,
+            warp_drop_requested: false,
+            integrator: Integrator::default(),
+            difficulty: Difficulty::default(),
             fuel: 1.0,
             credits: 10,
             total_credits: 10,
+            decoys: 1,
+            jammers: 0,
             manouver_mode: false,
             manouver_dv: Vec2::ZERO,
             manouver_fuel: 0.0,
+            manouver_planner: false,
+            manouver_node: None,
+            manouver_nodes: vec![],
+            autopilot: None,
             contracts: vec![],
             selected_contract: None,
-            cargo_hold: [None; 3],
-            time_of_last_contract_spawned: 0.0,
+            cargo_hold: vec![],
+            tick_of_last_contract_spawned: 0,
         }
     }
 
+    pub fn time(&self) -> f32 {
+        ticks_to_time(self.tick)
+    }
+
     pub fn day(&self) -> u32 {
-        (self.time / 2500.0).ceil() as u32
+        self.tick.div_ceil(TICKS_PER_DAY)
     }
 
     pub fn steps(&self) -> u32 {
-        match self.speed {
-            GameSpeed::Paused => 0,
-            GameSpeed::Normal => 1,
-            GameSpeed::Fast => 2,
+        self.speed.steps()
+    }
+
+    // Speed changes at the beginning of the next frame, so that all the
+    // systems get the same number of steps in the current one
+    pub fn drop_warp(&mut self) {
+        if self.speed.is_warp() {
+            self.warp_drop_requested = true;
         }
     }
 
+    pub fn drop_warp_near(&mut self, pursuer: Vec2, player: Vec2) {
+        if pursuer.distance(player) < WARP_DROP_DISTANCE {
+            self.drop_warp();
+        }
+    }
+
+    pub fn suggested_dv(&self) -> Option<Vec2> {
+        self.autopilot.as_ref().and_then(|autopilot| autopilot.dv)
+    }
+
+    pub fn reserved_fuel(&self) -> f32 {
+        manouvers::reserved_fuel(self)
+    }
+
+    pub fn free_hold_slots(&self) -> usize {
+        let taken: usize = self
+            .cargo_hold
+            .iter()
+            .map(|contract| contract.cargo.ty().size)
+            .sum();
+
+        CARGO_HOLD_SLOTS.saturating_sub(taken)
+    }
+
+    pub fn cargo_at_slot(&self, slot: usize) -> Option<&Contract> {
+        let mut first_slot = 0;
+
+        self.cargo_hold.iter().find(|contract| {
+            first_slot += contract.cargo.ty().size;
+            slot < first_slot
+        })
+    }
+
+    pub fn cargo_mass(&self) -> f32 {
+        self.cargo_hold
+            .iter()
+            .map(|contract| contract.cargo.ty().mass)
+            .sum()
+    }
+
     pub fn is_paused(&self) -> bool {
         self.speed == GameSpeed::Paused
     }
@@ -76,14 +176,39 @@ pub enum GameSpeed {
     Paused,
     Normal,
     Fast,
+    Warp4,
+    Warp8,
+    Warp16,
+    Warp32,
 }
 
 impl GameSpeed {
-    fn delta(&self) -> f32 {
+    pub fn steps(&self) -> u32 {
         match self {
-            GameSpeed::Paused => 0.0,
-            GameSpeed::Normal => 1.0,
-            GameSpeed::Fast => 2.0,
+            GameSpeed::Paused => 0,
+            GameSpeed::Normal => 1,
+            GameSpeed::Fast => 2,
+            GameSpeed::Warp4 => 4,
+            GameSpeed::Warp8 => 8,
+            GameSpeed::Warp16 => 16,
+            GameSpeed::Warp32 => 32,
         }
     }
+
+    pub fn is_warp(&self) -> bool {
+        self.steps() > 2
+    }
+
+    pub fn next_warp(&self) -> Self {
+        match self {
+            GameSpeed::Warp4 => GameSpeed::Warp8,
+            GameSpeed::Warp8 => GameSpeed::Warp16,
+            GameSpeed::Warp16 => GameSpeed::Warp32,
+            _ => GameSpeed::Warp4,
+        }
+    }
+}
+
+pub fn ticks_to_time(tick: u32) -> f32 {
+    ((tick as f64) * (DT as f64)) as f32
 }
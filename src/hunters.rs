@@ -0,0 +1,303 @@
+use crate::contracts::MIN_DELIVERY_DISTANCE;
+use crate::prelude::*;
+use crate::sim::FramePositions;
+
+const HUNTER_SPEED: f32 = 0.8;
+const HUNTER_THRUST: f32 = 0.005;
+const HUNTER_FUEL: f32 = 150.0;
+const HUNTERS_PER_BOUNTY: usize = 2;
+
+// Unlike the police, hunters can't be bribed - the only way to get rid of
+// them is to pay the bounty off at a guild planet
+pub struct HunterState {
+    bounty: u32,
+    spawn_at: Option<u32>,
+    docked_at: Option<usize>,
+    hunters: Vec<Hunter>,
+}
+
+impl HunterState {
+    pub fn bounty(&self) -> u32 {
+        self.bounty
+    }
+
+    pub fn add_bounty(
+        &mut self,
+        rng: &mut dyn RngCore,
+        game: &Game,
+        bounty: u32,
+    ) {
+        self.bounty += bounty;
+
+        if self.spawn_at.is_none() {
+            self.spawn_at = Some(
+                game.tick
+                    + rng.gen_range(
+                        10 * TICKS_PER_SECOND..20 * TICKS_PER_SECOND,
+                    ),
+            );
+        }
+    }
+
+    fn clear_bounty(&mut self) {
+        self.bounty = 0;
+        self.spawn_at = None;
+        self.send_hunters_away();
+    }
+
+    fn send_hunters_away(&mut self) {
+        for hunter in &mut self.hunters {
+            hunter.leaving = true;
+        }
+    }
+}
+
+static mut STATE: HunterState = HunterState {
+    bounty: 0,
+    spawn_at: None,
+    docked_at: None,
+    hunters: Vec::new(),
+};
+
+pub unsafe fn get() -> &'static HunterState {
+    &STATE
+}
+
+pub unsafe fn get_mut() -> &'static mut HunterState {
+    &mut STATE
+}
+
+pub fn tic(
+    rng: &mut dyn RngCore,
+    camera: &Camera,
+    player: &Player,
+    planets: &[Planet],
+    game: &mut Game,
+    decoy: Option<Ship>,
+) -> bool {
+    let state = unsafe { &mut STATE };
+
+    if !player.is_caught {
+        dock(state, player, planets, game);
+
+        // Hunters that ran out of fuel or crashed get replaced
+        if state.bounty > 0
+            && state.spawn_at.is_none()
+            && state.hunters.iter().all(|hunter| hunter.is_stranded())
+        {
+            state.spawn_at = Some(game.tick + 15 * TICKS_PER_SECOND);
+        }
+
+        if state.spawn_at.is_some_and(|spawn_at| game.tick >= spawn_at) {
+            state.spawn_at = None;
+
+            for _ in 0..HUNTERS_PER_BOUNTY {
+                state.hunters.push(Hunter::rand(rng));
+            }
+
+            msgs::add_random(rng, msgs::pools::HUNTERS_ARRIVED);
+            game.drop_warp();
+
+            sfx(
+                1,
+                SfxOptions {
+                    note: 3,
+                    octave: 1,
+                    duration: 10,
+                    channel: 0,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    // ---
+
+    let mut caught = false;
+
+    // Hunters chase the decoy, if there's one
+    let target = decoy.unwrap_or(player.ship);
+    let positions = FramePositions::new(game, planets);
+
+    for hunter in &mut state.hunters {
+        let hunter_pos = camera.world_to_screen(hunter.ship.pos);
+
+        let hunter_dir = if hunter.leaving {
+            (hunter.ship.pos - player.ship.pos)
+                .try_normalize()
+                .unwrap_or(Vec2::Y)
+        } else {
+            let time_to_impact =
+                hunter.ship.pos.distance(target.pos) / HUNTER_SPEED;
+
+            let intercept_at = target.pos + target.vel * time_to_impact;
+
+            (intercept_at - hunter.ship.pos)
+                .try_normalize()
+                .unwrap_or(Vec2::Y)
+        };
+
+        let hunter_engine_at = ShipSprite::hunter()
+            .at(hunter_pos)
+            .rot(PI - hunter_dir.angle_between(Vec2::Y))
+            .scale(camera.scale.max(0.3))
+            .engine(hunter.fuel > 0.0)
+            .draw(Some(game));
+
+        if player.is_caught {
+            continue;
+        }
+
+        if !hunter.leaving {
+            Localizator::hunter(hunter_pos).draw();
+
+            game.drop_warp_near(hunter.ship.pos, player.ship.pos);
+        }
+
+        for step in 0..game.steps() {
+            let tick = game.tick + step;
+
+            if hunter.thrust(hunter_dir * HUNTER_SPEED) != Vec2::ZERO {
+                particles::spawn_exhaust(
+                    camera.screen_to_world(hunter_engine_at),
+                    -hunter_dir * HUNTER_SPEED,
+                );
+            }
+
+            sim::eval_ship(
+                game.integrator,
+                &mut hunter.ship,
+                planets,
+                positions.at(tick),
+                positions.at(tick + 1),
+            );
+
+            hunter.crashed = planets::collision_at(
+                planets,
+                positions.at(tick + 1),
+                hunter.ship.pos,
+            )
+            .is_some();
+
+            if hunter.crashed {
+                break;
+            }
+
+            if !hunter.leaving
+                && hunter.ship.pos.distance(player.ship.pos) <= 90.0
+            {
+                caught = true;
+            }
+        }
+    }
+
+    // Hunters don't take bribes - they take everything
+    let mut game_over = false;
+
+    if caught {
+        if game.credits == 0 {
+            game_over = true;
+        } else {
+            msgs::add(format!(
+                "Bounty hunters robbed you, $-{}k",
+                game.credits
+            ));
+
+            // Robbery doesn't pay the bounty off - these hunters are done, but
+            // new ones are going to come
+            game.credits = 0;
+            state.send_hunters_away();
+        }
+    }
+
+    // ---
+
+    let crashed = state.hunters.extract_if(|hunter| hunter.crashed).count();
+
+    if crashed > 0 {
+        msgs::add_random(rng, msgs::pools::HUNTERS_CRASHED);
+    }
+
+    state
+        .hunters
+        .extract_if(|hunter| {
+            // Leaving hunters are as good as out of fuel
+            let fuel = if hunter.leaving { 0.0 } else { hunter.fuel };
+
+            hunter.ship.can_be_towed_away(fuel, camera)
+        })
+        .for_each(drop);
+
+    game_over
+}
+
+fn dock(
+    state: &mut HunterState,
+    player: &Player,
+    planets: &[Planet],
+    game: &mut Game,
+) {
+    let docked_at = planets.iter().position(|planet| {
+        planet.guild
+            && planet.pos.distance(player.ship.pos)
+                < planet.radius + MIN_DELIVERY_DISTANCE
+    });
+
+    // Handle each docking just once
+    if docked_at == state.docked_at {
+        return;
+    }
+
+    state.docked_at = docked_at;
+
+    if docked_at.is_none() || state.bounty == 0 {
+        return;
+    }
+
+    if game.credits < state.bounty {
+        msgs::add(format!(
+            "The guild wants ${}k to clear your bounty.",
+            state.bounty
+        ));
+
+        return;
+    }
+
+    game.credits -= state.bounty;
+    msgs::add(format!("Bounty paid off, $-{}k", state.bounty));
+    audio::play(sounds::COIN);
+
+    state.clear_bounty();
+}
+
+struct Hunter {
+    ship: Ship,
+    fuel: f32,
+    leaving: bool,
+    crashed: bool,
+}
+
+impl Hunter {
+    fn rand(rng: &mut dyn RngCore) -> Self {
+        Self {
+            ship: Ship {
+                pos: vec2(
+                    rng.gen_range(-10000.0..10000.0),
+                    rng.gen_range(-10000.0..10000.0),
+                ),
+                vel: Vec2::ZERO,
+            },
+            fuel: HUNTER_FUEL,
+            leaving: false,
+            crashed: false,
+        }
+    }
+
+    fn thrust(&mut self, target_vel: Vec2) -> Vec2 {
+        self.ship.thrust(&mut self.fuel, target_vel, HUNTER_THRUST)
+    }
+
+    fn is_stranded(&self) -> bool {
+        self.fuel <= 0.0 || self.leaving
+    }
+}
@@ -1,3 +1,4 @@
+use crate::police::PoliceClass;
 use crate::prelude::*;
 
 static mut STATE: State = State::AwaitingAnyKey;
@@ -12,7 +13,7 @@ enum State {
     },
 }
 
-pub fn tic() -> bool {
+pub fn tic(game: &mut Game) -> bool {
     let state = unsafe { &mut STATE };
     let time = time() / 1000.0;
 
@@ -46,6 +47,11 @@ pub fn tic() -> bool {
             .draw();
     }
 
+    Text::new(format!("Difficulty: < {} >", game.difficulty.name()))
+        .at(vec2(WIDTH as f32, 104.0 + text_offset))
+        .align_center()
+        .draw();
+
     // ---
 
     match state {
@@ -86,11 +92,12 @@ pub fn tic() -> bool {
 
                 let police_rot = get_rotation(police_vel);
 
-                let police_engine_at = ShipSprite::police(true)
-                    .at(police_at)
-                    .rot(police_rot)
-                    .engine(true)
-                    .draw(None);
+                let police_engine_at =
+                    ShipSprite::police(PoliceClass::Interceptor, true)
+                        .at(police_at)
+                        .rot(police_rot)
+                        .engine(true)
+                        .draw(None);
 
                 particles::spawn_exhaust(
                     police_engine_at,
@@ -103,6 +110,14 @@ pub fn tic() -> bool {
 
             // ---
 
+            if btnp(btns::LEFT, 16, 4) {
+                game.difficulty = game.difficulty.prev();
+            }
+
+            if btnp(btns::RIGHT, 16, 4) {
+                game.difficulty = game.difficulty.next();
+            }
+
             if any_key() {
                 *state = State::AnimatingOut {
                     ship_positions,
@@ -129,7 +144,7 @@ pub fn tic() -> bool {
                 let ship = if ship_idx == 0 {
                     ShipSprite::player()
                 } else {
-                    ShipSprite::police(true)
+                    ShipSprite::police(PoliceClass::Interceptor, true)
                 };
 
                 let ship_engine_at = ship
@@ -185,13 +200,22 @@ fn get_rotation(vel: Vec2) -> f32 {
 }
 
 fn any_key() -> bool {
+    // Left & right pick the difficulty instead
     for i in 0..32 {
+        if i % 8 == btns::LEFT || i % 8 == btns::RIGHT {
+            continue;
+        }
+
         if btn(i) {
             return true;
         }
     }
 
     for i in 0..65 {
+        if i == keys::LEFT || i == keys::RIGHT {
+            continue;
+        }
+
         if key(i) {
             return true;
         }
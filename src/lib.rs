@@ -4,23 +4,32 @@
 #[macro_use]
 mod tic80;
 
+#[cfg(not(test))]
 mod alloc;
 mod audio;
+mod autopilot;
+mod bribe;
 mod camera;
 mod contracts;
+mod countermeasures;
+mod customs;
 mod death;
 mod fuel;
 mod game;
+mod hunters;
 mod intro;
 mod localizator;
 mod manouvers;
+mod market;
 mod mouse_mgr;
 mod msgs;
+mod orbit;
 mod particles;
 mod planet;
 mod planets;
 mod player;
 mod police;
+mod reputation;
 mod screen_shake;
 mod selector;
 mod ship;
@@ -37,7 +46,7 @@ mod prelude {
 
     pub(crate) use crate::arrow::Arrow;
     pub(crate) use crate::camera::Camera;
-    pub(crate) use crate::game::{Game, GameSpeed};
+    pub(crate) use crate::game::{ticks_to_time, Game, GameSpeed};
     pub(crate) use crate::localizator::Localizator;
     pub(crate) use crate::mouse_mgr::{
         mouse_left_hold, mouse_left_pressed, mouse_pos, mouse_right_pressed,
@@ -49,7 +58,7 @@ mod prelude {
     pub(crate) use crate::text::Text;
     pub(crate) use crate::tic80::*;
     pub(crate) use crate::utils::*;
-    pub(crate) use crate::{audio, msgs, particles, police, sim};
+    pub(crate) use crate::{audio, msgs, particles, planets, police, sim};
 }
 
 use rand::rngs::SmallRng;
@@ -68,7 +77,9 @@ enum State {
 
 enum GameOverReason {
     Caught,
+    Hunted,
     FellIntoTheSun,
+    CrashedIntoPlanet,
 }
 
 static mut STATE: State = State::Intro;
@@ -100,7 +111,7 @@ pub fn tic() {
 
     match state {
         State::Intro => {
-            if intro::tic() {
+            if intro::tic(unsafe { game::get_mut() }) {
                 *rng = SmallRng::seed_from_u64(time().to_bits() as u64);
                 *state = State::Spawning;
             }
@@ -112,8 +123,9 @@ pub fn tic() {
         State::Spawning | State::Playing | State::GameOver { .. } => unsafe {
             audio::tic();
 
-            if game::get().time == 0.0 {
+            if game::get().tick == 0 {
                 planets::init(planets::galaxies::gamma());
+                market::init(rng, planets::get());
             }
 
             if let State::Spawning | State::Playing = state {
@@ -129,10 +141,10 @@ pub fn tic() {
             }
 
             if let State::Playing = state {
-                if death::tic(player::get(), planets::get()) {
-                    *state = State::GameOver {
-                        reason: GameOverReason::FellIntoTheSun,
-                    };
+                if let Some(reason) =
+                    death::tic(game::get(), player::get_mut(), planets::get())
+                {
+                    *state = State::GameOver { reason };
                 }
             }
 
@@ -142,6 +154,8 @@ pub fn tic() {
                 player::get(),
                 planets::get(),
                 game::get_mut(),
+                countermeasures::decoy(),
+                reputation::get(),
             ) {
                 *state = State::GameOver {
                     reason: GameOverReason::Caught,
@@ -150,13 +164,47 @@ pub fn tic() {
                 player::get_mut().is_caught = true;
             }
 
+            if hunters::tic(
+                rng,
+                camera::get(),
+                player::get(),
+                planets::get(),
+                game::get_mut(),
+                countermeasures::decoy(),
+            ) {
+                *state = State::GameOver {
+                    reason: GameOverReason::Hunted,
+                };
+
+                player::get_mut().is_caught = true;
+            }
+
             if let State::Playing = state {
-                manouvers::tic(
-                    camera::get(),
-                    game::get_mut(),
-                    player::get_mut(),
-                    planets::get(),
-                );
+                // Bribe negotiation is modal, so no burns can be made then
+                if !police::get().is_negotiating() {
+                    autopilot::tic(
+                        camera::get(),
+                        game::get_mut(),
+                        player::get_mut(),
+                        planets::get(),
+                    );
+
+                    manouvers::tic(
+                        camera::get(),
+                        game::get_mut(),
+                        player::get_mut(),
+                        planets::get(),
+                    );
+
+                    countermeasures::tic(
+                        rng,
+                        camera::get(),
+                        game::get_mut(),
+                        player::get(),
+                        planets::get(),
+                        police::get_mut(),
+                    );
+                }
 
                 contracts::tic(
                     rng,
@@ -165,6 +213,9 @@ pub fn tic() {
                     player::get_mut(),
                     planets::get(),
                     police::get_mut(),
+                    hunters::get_mut(),
+                    market::get_mut(),
+                    reputation::get_mut(),
                 );
 
                 fuel::tic(
@@ -174,10 +225,23 @@ pub fn tic() {
                     planets::get(),
                 );
 
+                market::tic(rng, game::get(), planets::get());
                 msgs::tic(game::get());
-                ui::tic(game::get_mut(), camera::get(), police::get());
+                ui::tic(
+                    game::get_mut(),
+                    camera::get(),
+                    player::get(),
+                    planets::get(),
+                    police::get(),
+                    hunters::get(),
+                    reputation::get(),
+                );
                 localizator::tic();
-                sim::tic(game::get(), player::get_mut(), planets::get_mut());
+                sim::tic(
+                    game::get_mut(),
+                    player::get_mut(),
+                    planets::get_mut(),
+                );
             }
 
             if let State::GameOver { reason } = state {
@@ -189,12 +253,23 @@ pub fn tic() {
                 match reason {
                     GameOverReason::Caught => {
                         text = "Ouch, you've been caught!";
-                        ship = ShipSprite::police(true);
+                        ship = ShipSprite::police(
+                            police::PoliceClass::Interceptor,
+                            true,
+                        );
+                    }
+                    GameOverReason::Hunted => {
+                        text = "Ouch, the bounty hunters got you!";
+                        ship = ShipSprite::hunter();
                     }
                     GameOverReason::FellIntoTheSun => {
                         text = "Ouch, you fell into the sun!";
                         ship = ShipSprite::player();
                     }
+                    GameOverReason::CrashedIntoPlanet => {
+                        text = "Ouch, you crashed into a planet!";
+                        ship = ShipSprite::player();
+                    }
                 };
 
                 for x in 0..WIDTH {
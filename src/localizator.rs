@@ -20,6 +20,13 @@ impl Localizator {
         }
     }
 
+    pub fn hunter(at: Vec2) -> Self {
+        Self {
+            at,
+            ty: LocalizatorTy::Hunter,
+        }
+    }
+
     pub fn contract(at: Vec2) -> Self {
         Self {
             at,
@@ -53,6 +60,14 @@ impl Localizator {
                 }
             }
 
+            LocalizatorTy::Hunter => {
+                if blink() {
+                    1
+                } else {
+                    13
+                }
+            }
+
             LocalizatorTy::Contract => {
                 if blink() {
                     3
@@ -91,6 +106,7 @@ impl Localizator {
 enum LocalizatorTy {
     Player,
     Police,
+    Hunter,
     Contract,
 }
 
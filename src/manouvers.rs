@@ -1,7 +1,29 @@
+use crate::contracts::{MIN_ACCEPT_DISTANCE, MIN_DELIVERY_DISTANCE};
+use crate::game::TICKS_PER_DAY;
 use crate::prelude::*;
 use crate::screen_shake::add_shake;
+use crate::sim::{Touch, TrajectoryCache, TrajectoryStep};
 
-const MAX_MANOUVER_LENGTH: f32 = 10.0;
+// For an empty ship on a full tank
+pub const MAX_MANOUVER_LENGTH: f32 = 10.0;
+
+// Fuel & cargo come on top of that
+const DRY_MASS: f32 = 10.0;
+
+const FUEL_MASS: f32 = 10.0;
+
+// A full tank weighs as much as the empty ship, hence ln 2
+const EXHAUST_VELOCITY: f32 = MAX_MANOUVER_LENGTH / std::f32::consts::LN_2;
+
+static mut PREVIEW: TrajectoryCache = TrajectoryCache::new();
+
+// Fuel is worked out only once the node gets executed, since the ship can
+// get lighter (or heavier) till then
+#[derive(Clone, Copy)]
+pub struct ManouverNode {
+    pub tick: u32,
+    pub dv: Vec2,
+}
 
 pub fn tic(
     camera: &Camera,
@@ -13,7 +35,17 @@ pub fn tic(
         return;
     }
 
-    let vec = camera.world_to_screen(player.ship.pos) - mouse_pos();
+    if keyp(keys::N, 0, 0) && !game.manouver_mode {
+        game.manouver_planner = !game.manouver_planner;
+    }
+
+    // Where the manouver starts - either at the ship or at a planned node
+    let origin = game
+        .manouver_node
+        .map(|(_, pos)| pos)
+        .unwrap_or(player.ship.pos);
+
+    let vec = camera.world_to_screen(origin) - mouse_pos();
     let dist = vec.length();
 
     game.manouver_dv = {
@@ -22,9 +54,9 @@ pub fn tic(
         let manouver_sensitivity = lerp(0.02, 0.08, manouver_len / 64.0);
 
         let manouver = manouver_dir * manouver_len * manouver_sensitivity;
-        let max_manouver_len = game.fuel * MAX_MANOUVER_LENGTH;
+        let max_manouver_len = max_dv(game);
 
-        if max_manouver_len == 0.0 {
+        if max_manouver_len <= 0.0 {
             vec2(0.0, 0.0)
         } else if manouver.length() <= max_manouver_len {
             manouver
@@ -33,74 +65,328 @@ pub fn tic(
         }
     };
 
-    game.manouver_fuel =
-        (game.manouver_dv.length() / MAX_MANOUVER_LENGTH).max(0.04);
+    if let Some(dv) = game.suggested_dv() {
+        game.manouver_dv = dv;
+    }
+
+    game.manouver_fuel = fuel_for(game, game.manouver_dv.length());
 
-    if !game.manouver_mode && dist < 10.0 {
+    // ---
+
+    let is_over_ship =
+        !game.manouver_mode && game.autopilot.is_none() && dist < 10.0;
+
+    if is_over_ship {
         Selector::new(camera.world_to_screen(player.ship.pos))
             .size(vec2(16.0, 16.0))
             .draw();
 
-        game.manouver_mode |= mouse_left_pressed() && !player.is_just_spawned;
+        if mouse_left_pressed() && !player.is_just_spawned {
+            game.manouver_mode = true;
+            game.manouver_node = None;
+        }
     }
 
     if game.manouver_mode && (mouse_right_pressed() || key(keys::X)) {
         game.manouver_mode = false;
+        game.manouver_node = None;
     }
 
     if game.manouver_mode && !mouse_left_hold() {
         game.manouver_mode = false;
 
-        if game.manouver_dv.length() > 0.0 {
-            if game.fuel <= 0.00001 {
+        if let Some((tick, _)) = game.manouver_node.take() {
+            if game.manouver_dv.length() > 0.0 {
+                game.manouver_nodes.push(ManouverNode {
+                    tick,
+                    dv: game.manouver_dv,
+                });
+
+                game.manouver_nodes.sort_by_key(|node| node.tick);
+
+                msgs::add("Manouver planned.");
+            }
+        } else if game.manouver_dv.length() > 0.0 {
+            if game.fuel - game.reserved_fuel() <= 0.00001
+                || !burn(game, &mut player.ship, game.manouver_dv)
+            {
                 msgs::add("You don't have fuel.");
             } else {
-                player.ship.vel += game.manouver_dv;
-                game.fuel -= game.manouver_fuel;
-
-                if game.fuel < 0.01 {
-                    game.fuel = 0.0;
-                }
-
-                add_shake();
-
-                sfx(
-                    4,
-                    SfxOptions {
-                        note: 0,
-                        octave: 3,
-                        duration: 20,
-                        volume_left: 8,
-                        volume_right: 8,
-                        ..Default::default()
-                    },
-                );
-
                 game.speed = GameSpeed::Normal;
             }
         }
     }
 
-    if game.manouver_mode && game.manouver_dv.length() > 0.0 {
-        let mut player = player.ship;
+    // ---
+
+    let show_preview = (game.manouver_mode || game.suggested_dv().is_some())
+        && game.manouver_dv.length() > 0.0;
+    let show_plan = game.manouver_planner || !game.manouver_nodes.is_empty();
+
+    if !show_preview && !show_plan {
+        return;
+    }
+
+    let mut ship = player.ship;
+    let mut nodes = game.manouver_nodes.clone();
 
-        player.vel += game.manouver_dv;
+    if show_preview {
+        if let Some((tick, _)) = game.manouver_node {
+            nodes.push(ManouverNode {
+                tick,
+                dv: game.manouver_dv,
+            });
+        } else {
+            ship.vel += game.manouver_dv;
+        }
+    }
 
-        let mut prev_step = player.pos;
-        let steps = sim::trajectory(game, &player, planets);
-        let display = time() % 500.0 < 250.0;
+    let mut approaches = approaches(game);
+    let mpos = mouse_pos();
+    let mut prev_step = ship.pos;
+    let mut prev_tick = game.tick;
+    let mut hovered_step: Option<TrajectoryStep> = None;
+    let mut hovered_dist = 5.0;
+    let steps = unsafe { PREVIEW.update(game, &ship, planets, &nodes) };
+    let display = time() % 500.0 < 250.0;
 
-        for step in steps {
-            let p1 = camera.world_to_screen(prev_step);
-            let p2 = camera.world_to_screen(step.pos);
+    for &step in steps {
+        let p1 = camera.world_to_screen(prev_step);
+        let p2 = camera.world_to_screen(step.pos);
 
-            let display = if step.touches { display } else { true };
+        let (display, color) = match step.touches {
+            Some(Touch::Approaching) => (display, step.color),
+            Some(Touch::Impact) => (display, 2),
+            None => (true, step.color),
+        };
 
-            if display {
-                line(p1.x, p1.y, p2.x, p2.y, step.color);
-            }
+        if display {
+            line(p1.x, p1.y, p2.x, p2.y, color);
+        }
+
+        if step.node.is_some() {
+            circb(p2.x as i32, p2.y as i32, 3, 12);
+        }
+
+        if p2.distance(mpos) < hovered_dist {
+            hovered_step = Some(step);
+            hovered_dist = p2.distance(mpos);
+        }
+
+        for approach in &mut approaches {
+            approach.update(planets, prev_tick, prev_step, step);
+        }
+
+        prev_step = step.pos;
+        prev_tick = step.tick;
+    }
+
+    for approach in &approaches {
+        approach.draw(camera, game, planets);
+    }
+
+    if game.manouver_mode || game.autopilot.is_some() || is_over_ship {
+        return;
+    }
+
+    let Some(step) = hovered_step else {
+        return;
+    };
+
+    let at = camera.world_to_screen(step.pos);
+
+    Selector::new(at).size(vec2(10.0, 10.0)).draw();
+
+    if let Some(node_tick) = step.node {
+        if mouse_right_pressed() {
+            game.manouver_nodes.retain(|node| node.tick != node_tick);
+            msgs::add("Manouver removed.");
+        }
+    } else if game.manouver_planner && mouse_left_pressed() {
+        game.manouver_mode = true;
+        game.manouver_node = Some((step.tick, step.pos));
+    }
+}
+
+// Closest approach of the predicted trajectory to a planet we care about
+struct Approach {
+    planet: usize,
+    zone: f32,
+    label: &'static str,
+    tick: u32,
+    pos: Vec2,
+    dist: f32,
+}
+
+impl Approach {
+    fn update(
+        &mut self,
+        planets: &[Planet],
+        prev_tick: u32,
+        prev_pos: Vec2,
+        step: TrajectoryStep,
+    ) {
+        // Look at the segment in the planet's frame of reference, so that we
+        // don't miss an approach happening in-between the steps
+        let a =
+            prev_pos - planets::position_at(planets, self.planet, prev_tick);
+
+        let b =
+            step.pos - planets::position_at(planets, self.planet, step.tick);
+
+        let t = closest_on_segment(a, b);
+        let dist = lerp(a, b, t).length();
 
-            prev_step = step.pos;
+        if dist < self.dist {
+            self.tick = prev_tick + ((step.tick - prev_tick) as f32 * t) as u32;
+            self.pos = lerp(prev_pos, step.pos, t);
+            self.dist = dist;
         }
     }
+
+    fn draw(&self, camera: &Camera, game: &Game, planets: &[Planet]) {
+        if self.dist == f32::MAX {
+            return;
+        }
+
+        let planet = &planets[self.planet];
+        let at = camera.world_to_screen(self.pos);
+        let reaches = self.dist <= planet.radius + self.zone;
+
+        let text = if reaches {
+            format!("{}!", self.label)
+        } else {
+            let dist = (self.dist - planet.radius).max(0.0);
+            let eta = (self.tick - game.tick) as f32 / TICKS_PER_DAY as f32;
+
+            format!("{:.0} in {:.1}d", dist, eta)
+        };
+
+        if reaches {
+            circb(at.x as i32, at.y as i32, 4, 5);
+        }
+
+        line(at.x - 2.0, at.y - 2.0, at.x + 2.0, at.y + 2.0, planet.color);
+        line(at.x - 2.0, at.y + 2.0, at.x + 2.0, at.y - 2.0, planet.color);
+
+        Text::new(text)
+            .at(at + vec2(5.0, -2.0))
+            .color(if reaches { 5 } else { planet.color as i32 })
+            .small()
+            .draw();
+    }
+}
+
+// Cargo destinations & sources of the available contracts
+fn approaches(game: &Game) -> Vec<Approach> {
+    let mut approaches: Vec<Approach> = Vec::new();
+
+    let deliveries = game.cargo_hold.iter().map(|contract| {
+        (contract.dst_planet, MIN_DELIVERY_DISTANCE, "DELIVER")
+    });
+
+    let pickups = game
+        .contracts
+        .iter()
+        .map(|contract| (contract.src_planet, MIN_ACCEPT_DISTANCE, "PICK UP"));
+
+    for (planet, zone, label) in deliveries.chain(pickups) {
+        if approaches.iter().any(|approach| approach.planet == planet) {
+            continue;
+        }
+
+        approaches.push(Approach {
+            planet,
+            zone,
+            label,
+            tick: 0,
+            pos: Vec2::ZERO,
+            dist: f32::MAX,
+        });
+    }
+
+    approaches
+}
+
+// Follows the rocket equation - the heavier the ship, the more fuel
+pub fn fuel_for(game: &Game, dv: f32) -> f32 {
+    fuel_for_mass(ship_mass(game, game.fuel), dv)
+}
+
+fn fuel_for_mass(wet_mass: f32, dv: f32) -> f32 {
+    let burnt_mass = wet_mass * (1.0 - (-dv / EXHAUST_VELOCITY).exp());
+
+    (burnt_mass / FUEL_MASS).max(0.04)
+}
+
+pub fn reserved_fuel(game: &Game) -> f32 {
+    let mut fuel = game.fuel;
+
+    for node in &game.manouver_nodes {
+        let wet_mass = ship_mass(game, fuel);
+
+        fuel -= fuel_for_mass(wet_mass, node.dv.length()).min(fuel);
+    }
+
+    game.fuel - fuel
+}
+
+pub fn max_dv(game: &Game) -> f32 {
+    let fuel = (game.fuel - game.reserved_fuel()).max(0.0);
+    let wet_mass = ship_mass(game, game.fuel);
+
+    EXHAUST_VELOCITY * (wet_mass / (wet_mass - fuel * FUEL_MASS)).ln()
+}
+
+fn ship_mass(game: &Game, fuel: f32) -> f32 {
+    DRY_MASS + fuel * FUEL_MASS + game.cargo_mass()
+}
+
+// Called by the simulation right before the ship is moved
+pub fn execute(game: &mut Game, ship: &mut Ship, tick: u32) {
+    while let Some(node) = game.manouver_nodes.first().copied() {
+        if node.tick > tick {
+            break;
+        }
+
+        game.manouver_nodes.remove(0);
+
+        if burn(game, ship, node.dv) {
+            msgs::add("Manouver executed.");
+        } else {
+            msgs::add("Not enough fuel for the planned manouver!");
+        }
+    }
+}
+
+// Returns whether there was enough fuel for the burn
+pub fn burn(game: &mut Game, ship: &mut Ship, dv: Vec2) -> bool {
+    let fuel = fuel_for(game, dv.length());
+
+    if game.fuel + 0.00001 < fuel {
+        return false;
+    }
+
+    ship.vel += dv;
+    game.fuel = (game.fuel - fuel).max(0.0);
+
+    if game.fuel < 0.01 {
+        game.fuel = 0.0;
+    }
+
+    add_shake();
+
+    sfx(
+        4,
+        SfxOptions {
+            note: 0,
+            octave: 3,
+            duration: 20,
+            volume_left: 8,
+            volume_right: 8,
+            ..Default::default()
+        },
+    );
+
+    true
 }
@@ -0,0 +1,184 @@
+use crate::contracts::{Cargo, CARGO_TYPES};
+use crate::prelude::*;
+
+const SHIPMENT_IMPACT: f32 = 0.2;
+
+// Per day
+const MEAN_REVERSION: f32 = 0.1;
+
+// Cargo is cheap where it's abundant and expensive where it's sought after;
+// contracts pay the difference between the two planets
+pub struct Market {
+    stocks: Vec<[Stock; CARGO_TYPES.len()]>,
+    day: u32,
+    is_open: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Stock {
+    // 0.0 - nothing in stock, 1.0 - warehouses are full
+    supply: f32,
+
+    // 0.0 - nobody wants it, 1.0 - everybody wants it
+    demand: f32,
+}
+
+impl Stock {
+    fn rand(rng: &mut dyn RngCore) -> Self {
+        Self {
+            supply: rng.gen(),
+            demand: rng.gen(),
+        }
+    }
+}
+
+impl Market {
+    // In thousands of credits
+    pub fn price(&self, planet: usize, cargo: Cargo) -> u32 {
+        let stock = &self.stocks[planet][cargo.id()];
+
+        let price = cargo.ty().base_price as f32
+            * (0.5 + stock.demand)
+            * (1.5 - stock.supply);
+
+        (price.round() as u32).max(1)
+    }
+
+    pub fn reward(
+        &self,
+        src_planet: usize,
+        dst_planet: usize,
+        cargo: Cargo,
+    ) -> u32 {
+        self.price(dst_planet, cargo)
+            .saturating_sub(self.price(src_planet, cargo))
+    }
+
+    pub fn pick_up(&mut self, planet: usize, cargo: Cargo) {
+        let stock = &mut self.stocks[planet][cargo.id()];
+
+        stock.supply = (stock.supply - SHIPMENT_IMPACT).max(0.0);
+    }
+
+    pub fn deliver(&mut self, planet: usize, cargo: Cargo) {
+        let stock = &mut self.stocks[planet][cargo.id()];
+
+        stock.supply = (stock.supply + SHIPMENT_IMPACT).min(1.0);
+        stock.demand = (stock.demand - SHIPMENT_IMPACT).max(0.0);
+    }
+}
+
+static mut MARKET: Market = Market {
+    stocks: Vec::new(),
+    day: 0,
+    is_open: false,
+};
+
+pub unsafe fn get_mut() -> &'static mut Market {
+    &mut MARKET
+}
+
+// Called before any contract can ask for prices
+pub fn init(rng: &mut dyn RngCore, planets: &[Planet]) {
+    let market = unsafe { &mut MARKET };
+
+    if market.stocks.len() != planets.len() {
+        market.stocks = planets
+            .iter()
+            .map(|_| [(); CARGO_TYPES.len()].map(|_| Stock::rand(rng)))
+            .collect();
+    }
+}
+
+pub fn tic(rng: &mut dyn RngCore, game: &Game, planets: &[Planet]) {
+    let market = unsafe { &mut MARKET };
+
+    // Prices drift a bit every day
+    while market.day < game.day() {
+        market.day += 1;
+
+        for stock in market.stocks.iter_mut().flatten() {
+            stock.supply = drift(rng, stock.supply);
+            stock.demand = drift(rng, stock.demand);
+        }
+    }
+
+    if keyp(keys::M, 0, 0) {
+        market.is_open = !market.is_open;
+    }
+
+    if market.is_open && game.selected_contract.is_none() {
+        draw(market, planets);
+    }
+}
+
+fn drift(rng: &mut dyn RngCore, level: f32) -> f32 {
+    let level = level
+        + MEAN_REVERSION * (0.5 - level)
+        + rng.gen_range(-SHIPMENT_IMPACT..SHIPMENT_IMPACT) * 0.5;
+
+    level.clamp(0.0, 1.0)
+}
+
+fn draw(market: &Market, planets: &[Planet]) {
+    const COL_W: i32 = 32;
+    const ROW_H: i32 = 9;
+
+    let rows = planets.len() as i32 - 1;
+    let box_w = 24 + COL_W * CARGO_TYPES.len() as i32;
+    let box_h = 28 + ROW_H * rows;
+    let box_x = WIDTH - box_w - 4;
+    let box_y = (HEIGHT - box_h) / 2;
+
+    rect(box_x, box_y, box_w, box_h, 0);
+    rectb(box_x, box_y, box_w, box_h, 14);
+
+    for (col, cargo) in Cargo::all().enumerate() {
+        let col_x = box_x + 24 + COL_W * col as i32;
+
+        Img::sprite_idx_with_size(cargo.ty().sprite, uvec2(2, 2))
+            .at(vec2((col_x + COL_W / 2 - 4) as f32, (box_y + 10) as f32))
+            .draw();
+
+        let prices =
+            || (1..planets.len()).map(|planet| market.price(planet, cargo));
+
+        let min = prices().min().unwrap_or(0);
+        let max = prices().max().unwrap_or(0);
+
+        for (row, price) in prices().enumerate() {
+            // Highlight the best places to buy & sell
+            let color = if price == max {
+                4
+            } else if price == min {
+                10
+            } else {
+                13
+            };
+
+            Text::new(format!("${}k", price))
+                .at(vec2(
+                    (col_x + COL_W - 4) as f32,
+                    (box_y + 20 + ROW_H * row as i32) as f32,
+                ))
+                .align_right()
+                .color(color)
+                .small()
+                .draw();
+        }
+    }
+
+    for (row, planet) in planets.iter().skip(1).enumerate() {
+        circ(box_x + 12, box_y + 22 + ROW_H * row as i32, 3, planet.color);
+    }
+
+    Text::new("M - close")
+        .at(vec2(
+            2.0 * (box_x + box_w / 2) as f32,
+            (box_y + box_h - 6) as f32,
+        ))
+        .align_center()
+        .color(14)
+        .small()
+        .draw();
+}
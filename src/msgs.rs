@@ -7,7 +7,7 @@ static mut MSGS: VecDeque<String> = VecDeque::new();
 static mut MSG: Option<Message> = None;
 
 pub fn tic(game: &Game) {
-    if game.manouver_mode {
+    if game.manouver_mode || game.autopilot.is_some() {
         return;
     }
 
@@ -84,6 +84,27 @@ pub fn add(msg: impl Display) {
     }
 }
 
+pub fn add_random(rng: &mut dyn RngCore, pool: &[&str]) {
+    if let Some(msg) = pool.choose(rng) {
+        add(msg);
+    }
+}
+
+pub mod pools {
+    pub static HUNTERS_ARRIVED: &[&str] = &[
+        "Bounty hunters are on your tail!",
+        "Someone put a price on your head.",
+        "Hunters incoming - they don't take bribes!",
+        "The guild sent its best. Run!",
+    ];
+
+    pub static HUNTERS_CRASHED: &[&str] = &[
+        "One hunter less to worry about.",
+        "That bounty wasn't worth it, huh?",
+        "Hunter down!",
+    ];
+}
+
 struct Message {
     text: String,
     offset: f32,
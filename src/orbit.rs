@@ -0,0 +1,215 @@
+use crate::game::TICKS_PER_DAY;
+use crate::prelude::*;
+
+// Our gravity falls off as 1/r, so orbits are not conics - turning points
+// and the period are found numerically
+pub struct Orbit {
+    pub planet: usize,
+    pub periapsis: f32,
+    pub apoapsis: Option<f32>,
+    pub period: Option<f32>,
+    pub speed: f32,
+    pub altitude: f32,
+    pub escaping: bool,
+}
+
+impl Orbit {
+    pub fn new(game: &Game, ship: &Ship, planets: &[Planet]) -> Self {
+        let planet_id = dominant_body(ship.pos, planets);
+        let planet = &planets[planet_id];
+        let mass = planet.mass;
+
+        let r = ship.pos - planet.pos;
+        let v = ship.vel - planets::velocity_at(planets, planet_id, game.tick);
+        let dist = r.length().max(1.0);
+
+        // Specific energy & angular momentum, relative to the dominant body
+        let energy = 0.5 * v.length_squared() + mass * dist.ln();
+        let momentum = r.perp_dot(v);
+
+        // Radial kinetic energy at given distance - positive between the
+        // turning points, zero at them
+        let radial = |r: f32| {
+            energy - mass * r.ln() - momentum * momentum / (2.0 * r * r)
+        };
+
+        let periapsis = bisect(&radial, 1.0, dist);
+        let apoapsis = find_apoapsis(&radial, dist);
+
+        let soi = sphere_of_influence(planet_id, planets);
+
+        let escaping = match apoapsis {
+            Some(apoapsis) => apoapsis > soi,
+            None => true,
+        };
+
+        let period = apoapsis
+            .map(|apoapsis| radial_period(&radial, mass, periapsis, apoapsis));
+
+        Self {
+            planet: planet_id,
+            periapsis: periapsis - planet.radius,
+            apoapsis: apoapsis.map(|apoapsis| apoapsis - planet.radius),
+            period,
+            speed: v.length(),
+            altitude: dist - planet.radius,
+            escaping,
+        }
+    }
+
+    pub fn draw(&self, at: Vec2, planets: &[Planet]) {
+        let planet = &planets[self.planet];
+
+        let name = if self.planet == 0 {
+            "Sun".to_string()
+        } else if planet.parent.is_some() {
+            format!("Moon {}", self.planet)
+        } else {
+            format!("Planet {}", self.planet)
+        };
+
+        let lines = [
+            (format!("Orbiting {}", name), planet.color as i32),
+            (
+                format!("Pe {:.0}", self.periapsis),
+                if self.periapsis < 0.0 { 2 } else { 12 },
+            ),
+            (
+                match self.apoapsis {
+                    Some(apoapsis) => format!("Ap {:.0}", apoapsis),
+                    None => "Ap -".to_string(),
+                },
+                12,
+            ),
+            (
+                match self.period {
+                    Some(period) => format!(
+                        "Period {:.1}d",
+                        period / DT / (TICKS_PER_DAY as f32)
+                    ),
+                    None => "Period -".to_string(),
+                },
+                12,
+            ),
+            (format!("Speed {:.0}u/s", self.speed * 1000.0), 12),
+            (format!("Alt {:.0}", self.altitude), 12),
+            if self.escaping {
+                ("ESCAPING".to_string(), 2)
+            } else {
+                ("BOUND".to_string(), 5)
+            },
+        ];
+
+        for (idx, (line, color)) in lines.into_iter().enumerate() {
+            Text::new(line)
+                .at(at + vec2(0.0, 7.0 * idx as f32))
+                .color(color)
+                .small()
+                .draw();
+        }
+    }
+}
+
+fn dominant_body(pos: Vec2, planets: &[Planet]) -> usize {
+    let mut best = 0;
+    let mut best_acc = 0.0;
+
+    for (planet_id, planet) in planets.iter().enumerate() {
+        let acc = planet.mass / planet.pos.distance(pos).max(1.0);
+
+        if acc > best_acc {
+            best = planet_id;
+            best_acc = acc;
+        }
+    }
+
+    best
+}
+
+// Returns radius at which the planet's pull equals its parent's pull; with
+// our 1/r gravity that's
+// r = a m / M
+// where
+// a = orbit radius
+// m = mass of the planet
+// M = mass of the parent
+fn sphere_of_influence(planet_id: usize, planets: &[Planet]) -> f32 {
+    if planet_id == 0 {
+        return f32::INFINITY;
+    }
+
+    let planet = &planets[planet_id];
+    let parent = &planets[planet.parent.unwrap_or(0)];
+
+    planet.orbit_radius * planet.mass / parent.mass
+}
+
+fn find_apoapsis(radial: &impl Fn(f32) -> f32, dist: f32) -> Option<f32> {
+    let mut max = dist;
+
+    for _ in 0..64 {
+        max *= 2.0;
+
+        if radial(max) < 0.0 {
+            return Some(bisect(radial, max, dist));
+        }
+    }
+
+    None
+}
+
+// Finds the turning point between `outside` (where `radial` is negative)
+// and `inside`
+fn bisect(radial: &impl Fn(f32) -> f32, outside: f32, inside: f32) -> f32 {
+    if radial(outside) >= 0.0 {
+        return outside;
+    }
+
+    let mut outside = outside;
+    let mut inside = inside;
+
+    for _ in 0..32 {
+        let mid = (outside + inside) / 2.0;
+
+        if radial(mid) < 0.0 {
+            outside = mid;
+        } else {
+            inside = mid;
+        }
+    }
+
+    (outside + inside) / 2.0
+}
+
+// Returns time it takes to go from periapsis to apoapsis and back, i.e.
+// T = 2 ∫ dr / √(2 radial(r))
+//
+// The integral is computed with substitution r = c - h cos θ, which gets rid
+// of the singularities at the turning points.
+fn radial_period(
+    radial: &impl Fn(f32) -> f32,
+    mass: f32,
+    periapsis: f32,
+    apoapsis: f32,
+) -> f32 {
+    let c = (apoapsis + periapsis) / 2.0;
+    let h = (apoapsis - periapsis) / 2.0;
+
+    // Almost circular orbit - use the epicyclic frequency, κ = √(2m) / r
+    if h < 0.001 * c {
+        return 2.0 * PI * c / (2.0 * mass).sqrt();
+    }
+
+    let steps = 32;
+    let mut period = 0.0;
+
+    for step in 0..steps {
+        let theta = PI * (step as f32 + 0.5) / (steps as f32);
+        let r = c - h * theta.cos();
+        let radial = radial(r).max(1e-9);
+
+        period += h * theta.sin() / (2.0 * radial).sqrt();
+    }
+
+    2.0 * period * PI / (steps as f32)
+}
@@ -5,14 +5,25 @@ pub struct Planet {
     pub pos: Vec2,
 
     // Oribital characteristics
+    //
+    // (for elliptical orbits `orbit_radius` is the semi-major axis and
+    // `orbit_phase` is the mean anomaly at time zero)
     pub orbit_radius: f32,
     pub orbit_phase: f32,
     pub orbit_speed: f32,
+    pub orbit_eccentricity: f32,
+    pub orbit_periapsis: f32,
 
     pub radius: f32,
     pub mass: f32,
     pub color: u8,
 
+    // Whether police runs customs inspections here
+    pub customs: bool,
+
+    // Whether bounties can be paid off here
+    pub guild: bool,
+
     // Parent planet index
     pub parent: Option<usize>,
 }
@@ -24,9 +35,13 @@ impl Planet {
             orbit_radius: 0.0,
             orbit_phase: 0.0,
             orbit_speed: 1.0,
+            orbit_eccentricity: 0.0,
+            orbit_periapsis: 0.0,
             radius: 0.0,
             mass: 0.0,
             color: 0,
+            customs: false,
+            guild: false,
             parent: None,
         }
     }
@@ -44,6 +59,16 @@ impl Planet {
         self
     }
 
+    pub const fn with_eccentricity(
+        mut self,
+        eccentricity: f32,
+        periapsis: f32,
+    ) -> Self {
+        self.orbit_eccentricity = eccentricity;
+        self.orbit_periapsis = periapsis;
+        self
+    }
+
     pub const fn with_mass(mut self, mass: f32) -> Self {
         self.mass = mass;
         self
@@ -59,6 +84,68 @@ impl Planet {
         self
     }
 
+    pub const fn with_customs(mut self) -> Self {
+        self.customs = true;
+        self
+    }
+
+    pub const fn with_guild(mut self) -> Self {
+        self.guild = true;
+        self
+    }
+
+    pub fn orbit_offset(&self, tick: u32) -> Vec2 {
+        // Wrap the tick around the orbital period while it's still exact -
+        // converting the whole tick to f32 would make planets jitter more and
+        // more as the game goes on
+        let period = (self.orbit_speed / DT) as f64;
+        let tick = ((tick as f64) % period) as f32;
+
+        let mean_anomaly = PI * 2.0 * tick / period as f32 + self.orbit_phase;
+
+        self.orbit_point(self.eccentric_anomaly(mean_anomaly))
+    }
+
+    // For circular orbits the eccentric anomaly is just the angle
+    pub fn orbit_point(&self, eccentric_anomaly: f32) -> Vec2 {
+        let a = self.orbit_radius;
+        let e = self.orbit_eccentricity;
+        let b = a * (1.0 - e * e).sqrt();
+
+        let point = vec2(
+            a * (eccentric_anomaly.cos() - e),
+            b * eccentric_anomaly.sin(),
+        );
+
+        point.rotate(Vec2::from_angle(self.orbit_periapsis))
+    }
+
+    // Solves Kepler's equation
+    // M = E - e sin E
+    // for E using Newton's method
+    fn eccentric_anomaly(&self, mean_anomaly: f32) -> f32 {
+        let e = self.orbit_eccentricity;
+
+        if e == 0.0 {
+            return mean_anomaly;
+        }
+
+        let m = mean_anomaly.rem_euclid(PI * 2.0);
+        let mut ea = if e < 0.8 { m } else { PI };
+
+        for _ in 0..8 {
+            let delta = (ea - e * ea.sin() - m) / (1.0 - e * ea.cos());
+
+            ea -= delta;
+
+            if delta.abs() < 1e-5 {
+                break;
+            }
+        }
+
+        ea
+    }
+
     pub fn collides_with(&self, obj: Vec2) -> bool {
         self.pos.distance(obj) <= self.radius
     }
@@ -17,7 +17,7 @@ pub fn init(mut planets: Vec<Planet>) {
         }
     }
 
-    sim::eval(0.0, &mut Ship::default(), &mut planets);
+    sim::eval_planets(0, &mut planets);
 
     unsafe {
         PLANETS = planets;
@@ -32,6 +32,60 @@ pub unsafe fn get_mut() -> &'static mut [Planet] {
     &mut PLANETS
 }
 
+// Follows the chain of parents, so moons of moons work, too
+pub fn position_at(planets: &[Planet], id: usize, tick: u32) -> Vec2 {
+    let planet = &planets[id];
+
+    let parent_pos = planet
+        .parent
+        .map(|parent_id| position_at(planets, parent_id, tick))
+        .unwrap_or_default();
+
+    parent_pos + planet.orbit_offset(tick)
+}
+
+// Cheaper than position_at() for each planet, since parents (which always
+// come before their moons) are solved only once
+pub fn positions_at(planets: &[Planet], tick: u32, positions: &mut Vec<Vec2>) {
+    positions.clear();
+
+    for planet in planets {
+        let parent_pos = planet
+            .parent
+            .map(|parent_id| positions[parent_id])
+            .unwrap_or_default();
+
+        positions.push(parent_pos + planet.orbit_offset(tick));
+    }
+}
+
+pub fn collision_at(
+    planets: &[Planet],
+    positions: &[Vec2],
+    pos: Vec2,
+) -> Option<usize> {
+    (0..planets.len()).find(|&planet_id| {
+        positions[planet_id].distance(pos) <= planets[planet_id].radius
+    })
+}
+
+// Sun doesn't count
+pub fn closest_to(planets: &[Planet], pos: Vec2) -> usize {
+    (1..planets.len())
+        .min_by(|&a, &b| {
+            let dist_a = planets[a].pos.distance(pos);
+            let dist_b = planets[b].pos.distance(pos);
+
+            dist_a.total_cmp(&dist_b)
+        })
+        .unwrap_or(0)
+}
+
+// Per millisecond, same as Ship::vel
+pub fn velocity_at(planets: &[Planet], id: usize, tick: u32) -> Vec2 {
+    (position_at(planets, id, tick + 1) - position_at(planets, id, tick)) / DT
+}
+
 pub fn tic(camera: &Camera) {
     let planets = unsafe { get() };
 
@@ -42,29 +96,35 @@ pub fn tic(camera: &Camera) {
             camera.world_to_screen(vec2(0.0, 0.0))
         };
 
-        draw_orbit(
-            orbit.x,
-            orbit.y,
-            planet.orbit_radius * camera.scale,
-            planet.orbit_phase,
-        );
+        draw_orbit(orbit, planet, camera.scale);
     }
 
     for planet in planets {
         let center = camera.world_to_screen(planet.pos).as_ivec2();
         let min_radius = if planet.parent.is_some() { 1.0 } else { 2.0 };
 
-        circ(
-            center.x,
-            center.y,
-            (camera.scale * planet.radius).max(min_radius) as i32,
-            planet.color,
-        );
+        let radius = (camera.scale * planet.radius).max(min_radius);
+
+        circ(center.x, center.y, radius as i32, planet.color);
+
+        if planet.customs {
+            Img::sprite_idx(263)
+                .at(center.as_vec2() + vec2(radius + 4.0, -radius - 4.0))
+                .draw();
+        }
+
+        if planet.guild {
+            Text::new("$")
+                .at(center.as_vec2() + vec2(radius + 2.0, radius - 2.0))
+                .color(13)
+                .small()
+                .draw();
+        }
     }
 }
 
-fn draw_orbit(x: f32, y: f32, r: f32, p: f32) {
-    let offset = vec2(x, y);
+fn draw_orbit(offset: Vec2, planet: &Planet, scale: f32) {
+    let p = planet.orbit_phase;
     let steps = 64;
 
     for step in (0..steps).step_by(2) {
@@ -74,24 +134,26 @@ fn draw_orbit(x: f32, y: f32, r: f32, p: f32) {
         let a1 = p + a1 * 2.0 * PI;
         let a2 = p + a2 * 2.0 * PI;
 
-        let p1 = offset + vec2(a1.cos(), a1.sin()) * r;
-        let p2 = offset + vec2(a2.cos(), a2.sin()) * r;
+        let p1 = offset + planet.orbit_point(a1) * scale;
+        let p2 = offset + planet.orbit_point(a2) * scale;
 
         line(p1.x, p1.y, p2.x, p2.y, 14);
     }
 }
 
 // Returns orbital period according to
-// T = 2π √(r³/GM)
+// T = 2π √(a³/GM)
 // where
 // T = orbital period
-// r = orbit radius
+// a = semi-major axis (i.e. orbit radius for circular orbits)
 // G = gravitational constant
 // M = mass of central body
-fn orbital_period(central_mass: f32, radius: f32) -> f32 {
+fn orbital_period(central_mass: f32, semi_major_axis: f32) -> f32 {
     const G: f32 = 6.6743e-11;
 
-    0.000001 * 2.0 * PI * (radius * radius * radius / (G * central_mass)).sqrt()
+    let a = semi_major_axis;
+
+    0.000001 * 2.0 * PI * (a * a * a / (G * central_mass)).sqrt()
 }
 
 pub mod galaxies {
@@ -116,7 +178,8 @@ pub mod galaxies {
                 .with_radius(28.0)
                 .with_mass(0.1)
                 .with_orbit(2500.0, 0.0)
-                .with_color(11),
+                .with_color(11)
+                .with_customs(),
             // 3
             Planet::new()
                 .with_radius(35.0)
@@ -128,7 +191,8 @@ pub mod galaxies {
                 .with_radius(40.0)
                 .with_mass(0.16)
                 .with_orbit(2800.0, 4.0)
-                .with_color(9),
+                .with_color(9)
+                .with_guild(),
             // 5
             Planet::new()
                 .with_radius(35.0)
@@ -140,7 +204,8 @@ pub mod galaxies {
                 .with_radius(100.0)
                 .with_mass(1.0)
                 .with_orbit(5000.0, 8.0)
-                .with_color(2),
+                .with_color(2)
+                .with_customs(),
             // 7
             Planet::new()
                 .with_radius(100.0)
@@ -152,19 +217,28 @@ pub mod galaxies {
                 .with_radius(9.0)
                 .with_mass(0.045)
                 .with_orbit(2500.0, 0.0)
-                .with_color(11),
+                .with_color(11)
+                .with_guild(),
             // 9
             Planet::new()
                 .with_radius(120.0)
                 .with_mass(4.4)
                 .with_orbit(10000.0, 10.0 + PI)
-                .with_color(5),
+                .with_color(5)
+                .with_customs(),
             // 10
             Planet::moon_of(9)
                 .with_radius(11.0)
                 .with_mass(0.05)
                 .with_orbit(1750.0, 0.0)
                 .with_color(11),
+            // 11 (comet)
+            Planet::new()
+                .with_radius(12.0)
+                .with_mass(0.02)
+                .with_orbit(6500.0, 1.0)
+                .with_eccentricity(0.7, 2.5)
+                .with_color(13),
         ]
     }
 }
@@ -5,6 +5,7 @@ pub struct Player {
     pub is_spawned: bool,
     pub is_just_spawned: bool,
     pub is_caught: bool,
+    pub landed_on: Option<usize>,
 }
 
 static mut PLAYER: Player = Player {
@@ -15,6 +16,7 @@ static mut PLAYER: Player = Player {
     is_spawned: false,
     is_just_spawned: false,
     is_caught: false,
+    landed_on: None,
 };
 
 pub unsafe fn get() -> &'static Player {
@@ -1,13 +1,37 @@
+use std::ops::Range;
+
+use crate::bribe::{Negotiation, Outcome};
 use crate::prelude::*;
+use crate::reputation::Reputation;
+use crate::sim::{FramePositions, TrajectoryCache, TrajectoryStep};
+
+// Police vehicles reach their top speed after this many ticks
+const MAX_SPEED_TICK: u32 = 60 * TICKS_PER_SECOND;
+
+// Grows with the wanted level, as more informants report on the player
+const SENSOR_RANGE: f32 = 6000.0;
+
+// How long a vehicle searches the player's last known position
+const SEARCH_TICKS: u32 = 20 * TICKS_PER_SECOND;
+
+// Below it, police sends patrols that have to find the player first
+const PATROL_WANTED_LEVEL: f32 = 0.4;
+
+const PATROL_DISTANCE: f32 = 1500.0;
+
+// After a refused bribe or an escape, police can't board the player again
+const GRACE_TICKS: u32 = 5 * TICKS_PER_SECOND;
 
-const STARTING_POLICE_SPEED: f32 = 0.2;
-const MAX_POLICE_SPEED: f32 = 0.75;
-const MAX_SPEED_TIME: f32 = 60_000.0;
+const BLOCKADE_DISTANCE: f32 = 300.0;
 
 pub struct PoliceState {
     wanted: f32,
-    dispatch_at: f32,
-    deducation_at: f32,
+    detected: bool,
+    negotiation: Option<Negotiation>,
+    grace_until: u32,
+    jammed_until: u32,
+    dispatch_at: u32,
+    deducation_at: u32,
     vehicles: Vec<PoliceVehicle>,
 }
 
@@ -19,12 +43,53 @@ impl PoliceState {
     pub fn increment_wanted_level(&mut self, t: f32) {
         self.wanted = (self.wanted + t).min(1.0);
     }
+
+    pub fn alert(&mut self, t: f32) {
+        self.increment_wanted_level(t);
+        self.dispatch_at = 0;
+    }
+
+    pub fn is_on_duty(&self) -> bool {
+        self.vehicles
+            .iter()
+            .any(|vehicle| vehicle.behavior.is_on_duty())
+    }
+
+    // Vehicles in pursuit lose track of the player
+    pub fn jam(&mut self, player: &Player, until: u32) {
+        self.jammed_until = until;
+        self.dispatch_at = self.dispatch_at.max(until);
+
+        for vehicle in &mut self.vehicles {
+            if vehicle.behavior.is_in_pursuit() {
+                vehicle.behavior = PoliceVehicleBehavior::Searching {
+                    last_known_pos: player.ship.pos,
+                    until: until + SEARCH_TICKS,
+                };
+            }
+        }
+    }
+
+    pub fn is_negotiating(&self) -> bool {
+        self.negotiation.is_some()
+    }
+
+    pub fn is_detected(&self) -> bool {
+        self.detected
+    }
 }
 
+// Shared by all the smart pursuers
+static mut PLAYER_PATH: TrajectoryCache = TrajectoryCache::new();
+
 static mut STATE: PoliceState = PoliceState {
     wanted: 0.0,
-    dispatch_at: 0.0,
-    deducation_at: 0.0,
+    detected: false,
+    negotiation: None,
+    grace_until: 0,
+    jammed_until: 0,
+    dispatch_at: 0,
+    deducation_at: 0,
     vehicles: Vec::new(),
 };
 
@@ -42,6 +107,8 @@ pub fn tic(
     player: &Player,
     planets: &[Planet],
     game: &mut Game,
+    decoy: Option<Ship>,
+    reputation: &Reputation,
 ) -> bool {
     let state = unsafe { &mut STATE };
 
@@ -52,12 +119,16 @@ pub fn tic(
         state.wanted = 1.0;
     }
 
+    let closest_planet = planets::closest_to(planets, player.ship.pos);
+    let police_factor = reputation.police_factor(closest_planet);
+
     if !player.is_caught {
-        if game.time >= state.deducation_at {
+        if game.tick >= state.deducation_at {
             let was_above_zero = state.wanted > 0.0;
 
             state.wanted = (state.wanted - 0.03).max(0.0);
-            state.deducation_at = game.time + rng.gen_range(1.0..5.0) * 1000.0;
+            state.deducation_at = game.tick
+                + rng.gen_range(TICKS_PER_SECOND..5 * TICKS_PER_SECOND);
 
             if state.wanted == 0.0 && was_above_zero {
                 msgs::add("Police seems to have lost interest in you.");
@@ -68,27 +139,47 @@ pub fn tic(
             }
         }
 
-        if game.time >= state.dispatch_at {
-            let vehicles_in_pursuit = state
+        if game.tick >= state.dispatch_at {
+            let vehicles_on_duty = state
                 .vehicles
                 .iter()
                 .filter(|vehicle| {
-                    matches!(vehicle.behavior, PoliceVehicleBehavior::InPursuit)
+                    vehicle.behavior.is_on_duty() && vehicle.fuel > 0.0
                 })
                 .count();
 
-            let max_vehicles_in_pursuit = (9.0 * state.wanted).ceil() as usize;
+            let max_vehicles_on_duty =
+                (9.0 * state.wanted * police_factor).ceil() as usize;
 
-            if state.wanted > 0.0
-                && vehicles_in_pursuit < max_vehicles_in_pursuit
-            {
-                let vehicles = if rng.gen_bool(0.5) { 1 } else { 3 };
+            if state.wanted > 0.0 && vehicles_on_duty < max_vehicles_on_duty {
+                let vehicles = if rng.gen_bool(0.5 * police_factor as f64) {
+                    3
+                } else {
+                    1
+                };
+                let patrol = state.wanted < PATROL_WANTED_LEVEL;
 
                 for _ in 0..vehicles {
-                    state.vehicles.push(PoliceVehicle::rand(rng));
+                    let mut vehicle =
+                        PoliceVehicle::rand(rng, game, state.wanted);
+
+                    if patrol && vehicle.behavior.is_in_pursuit() {
+                        vehicle.behavior =
+                            PoliceVehicleBehavior::patrolling(rng, planets);
+                    }
+
+                    state.vehicles.push(vehicle);
                 }
 
-                msgs::add({
+                msgs::add(if patrol {
+                    let msgs = [
+                        "Police patrol is in the area.",
+                        "Cops are sniffing around, lay low!",
+                        "Patrol incoming, stay out of sight!",
+                    ];
+
+                    *msgs.choose(rng).unwrap()
+                } else {
                     let msgs = [
                         "Police vehicle detected!",
                         "Oh no, it's the police!",
@@ -117,110 +208,293 @@ pub fn tic(
                 );
             }
 
-            state.dispatch_at = game.time + rng.gen_range(10.0..25.0) * 1000.0;
+            let dispatch_ticks =
+                rng.gen_range(10 * TICKS_PER_SECOND..25 * TICKS_PER_SECOND);
+
+            state.dispatch_at =
+                game.tick + (dispatch_ticks as f32 / police_factor) as u32;
         }
     }
 
     // ---
 
     let mut game_over = false;
-    let mut bribe = None;
+    let mut caught_by = None;
 
-    for vehicle in &mut state.vehicles {
-        let vehicle_pos = camera.world_to_screen(vehicle.pos);
+    // -- Sensors --
+    if !player.is_caught {
+        let range = SENSOR_RANGE * (1.0 + 2.0 * state.wanted);
 
-        let vehicle_dir = match vehicle.behavior {
-            PoliceVehicleBehavior::InPursuit => {
-                let to_player = player.ship.pos - vehicle.pos;
-                let player_vel = player.ship.vel;
+        for vehicle in &mut state.vehicles {
+            let sees_player = game.tick >= state.jammed_until
+                && vehicle.sees(&player.ship, planets, range);
+
+            match vehicle.behavior {
+                PoliceVehicleBehavior::InPursuit if !sees_player => {
+                    vehicle.behavior = PoliceVehicleBehavior::Searching {
+                        last_known_pos: player.ship.pos,
+                        until: game.tick + SEARCH_TICKS,
+                    };
+                }
 
-                let distance = to_player.length();
-                let time_to_impact = distance / player_vel.length();
+                PoliceVehicleBehavior::Searching { .. }
+                | PoliceVehicleBehavior::Patrolling { .. }
+                    if sees_player =>
+                {
+                    vehicle.behavior = PoliceVehicleBehavior::InPursuit;
+                }
 
-                let player_pos_at_impact =
-                    player.ship.pos + player_vel * time_to_impact;
+                PoliceVehicleBehavior::Searching { until, .. }
+                    if game.tick >= until =>
+                {
+                    vehicle.behavior =
+                        PoliceVehicleBehavior::patrolling(rng, planets);
+                }
 
-                let to_player_at_impact = player_pos_at_impact - vehicle.pos;
+                PoliceVehicleBehavior::Patrolling { waypoint }
+                    if vehicle.ship.pos.distance(waypoint)
+                        < PATROL_DISTANCE =>
+                {
+                    vehicle.behavior =
+                        PoliceVehicleBehavior::patrolling(rng, planets);
+                }
 
-                to_player_at_impact.normalize()
+                _ => (),
             }
-            PoliceVehicleBehavior::Escaping { dir } => dir,
-        };
+        }
+
+        let detected = state
+            .vehicles
+            .iter()
+            .any(|vehicle| vehicle.behavior.is_in_pursuit());
+
+        if detected && !state.detected {
+            msgs::add("You've been spotted!");
+        } else if !detected && state.detected && state.is_on_duty() {
+            msgs::add("You've lost them - for now.");
+        }
 
-        let speed = if game.time > MAX_SPEED_TIME {
-            MAX_POLICE_SPEED
+        state.detected = detected;
+    }
+
+    // ---
+
+    let any_smart_pursuer = state
+        .vehicles
+        .iter()
+        .any(|vehicle| vehicle.smart && vehicle.behavior.is_in_pursuit());
+
+    // Pursuers chase the decoy, if there's one
+    let target = decoy.unwrap_or(player.ship);
+
+    let player_path =
+        if any_smart_pursuer && decoy.is_none() && !player.is_caught {
+            unsafe {
+                PLAYER_PATH.update(
+                    game,
+                    &player.ship,
+                    planets,
+                    &game.manouver_nodes,
+                )
+            }
         } else {
-            remap(
-                game.time,
-                (0.0, MAX_SPEED_TIME),
-                (STARTING_POLICE_SPEED, MAX_POLICE_SPEED),
-            )
+            &[]
         };
 
-        let vehicle_vel = vehicle_dir * speed;
+    let positions = FramePositions::new(game, planets);
+
+    for vehicle in &mut state.vehicles {
+        let vehicle_pos = camera.world_to_screen(vehicle.ship.pos);
+        let speed = vehicle.class.speed(game.tick);
+
+        let vehicle_dir = match vehicle.behavior {
+            PoliceVehicleBehavior::InPursuit => {
+                let intercept_at = if vehicle.smart {
+                    smart_intercept(game, &vehicle.ship, player_path, speed)
+                } else {
+                    None
+                };
+
+                let intercept_at = intercept_at.unwrap_or_else(|| {
+                    naive_intercept(&vehicle.ship, &target, speed)
+                });
+
+                vehicle.dir_to(intercept_at)
+            }
+            PoliceVehicleBehavior::Searching { last_known_pos, .. } => {
+                vehicle.dir_to(last_known_pos)
+            }
+            PoliceVehicleBehavior::Patrolling { waypoint } => {
+                vehicle.dir_to(waypoint)
+            }
+            PoliceVehicleBehavior::Blockading { planet, .. } => {
+                vehicle.dir_to(planets[planet].pos)
+            }
+            PoliceVehicleBehavior::Escaping { dir } => dir,
+        };
 
         let vehicle_engine_at =
-            ShipSprite::police(vehicle.behavior.is_in_pursuit())
+            ShipSprite::police(vehicle.class, vehicle.behavior.is_in_pursuit())
                 .at(vehicle_pos)
                 .rot(PI - vehicle_dir.angle_between(Vec2::Y))
                 .scale(camera.scale.max(0.3))
-                .engine(true)
+                .engine(vehicle.fuel > 0.0)
                 .draw(Some(game));
 
         if player.is_caught {
             continue;
         }
 
-        if let PoliceVehicleBehavior::InPursuit = &vehicle.behavior {
+        if vehicle.behavior.is_on_duty() {
             Localizator::police(vehicle_pos).draw();
         }
 
-        for _ in 0..game.steps() {
-            vehicle.pos += vehicle_vel * DT;
+        if let PoliceVehicleBehavior::InPursuit = &vehicle.behavior {
+            game.drop_warp_near(vehicle.ship.pos, player.ship.pos);
+        }
 
-            particles::spawn_exhaust(
-                camera.screen_to_world(vehicle_engine_at),
-                -vehicle_vel,
-            );
+        for step in 0..game.steps() {
+            let tick = game.tick + step;
 
-            if let PoliceVehicleBehavior::InPursuit = &vehicle.behavior {
-                if bribe.is_none() && vehicle.collides_with(&player.ship) {
-                    if game.credits == 0 {
-                        game_over = true;
-                    } else {
-                        bribe = Some(rng.gen_range(1..20).min(game.credits));
-                    }
+            // Blockades keep their station next to the planet, everyone else
+            // flies at full speed
+            let target_vel = match vehicle.behavior {
+                PoliceVehicleBehavior::Blockading { planet, angle } => {
+                    let planet_pos = positions.at(tick)[planet];
+                    let planet_vel =
+                        (positions.at(tick + 1)[planet] - planet_pos) / DT;
+
+                    let station = planet_pos
+                        + Vec2::from_angle(angle)
+                            * (planets[planet].radius + BLOCKADE_DISTANCE);
+
+                    let to_station = station - vehicle.ship.pos;
+
+                    planet_vel
+                        + to_station.normalize_or_zero()
+                            * speed.min(to_station.length() / 1000.0)
                 }
+
+                _ => vehicle_dir * speed,
+            };
+
+            if vehicle.thrust(target_vel) != Vec2::ZERO {
+                particles::spawn_exhaust(
+                    camera.screen_to_world(vehicle_engine_at),
+                    -vehicle_dir * speed,
+                );
+            }
+
+            sim::eval_ship(
+                game.integrator,
+                &mut vehicle.ship,
+                planets,
+                positions.at(tick),
+                positions.at(tick + 1),
+            );
+
+            vehicle.crashed_into = planets::collision_at(
+                planets,
+                positions.at(tick + 1),
+                vehicle.ship.pos,
+            );
+
+            if vehicle.crashed_into.is_some() {
+                break;
+            }
+
+            if vehicle.behavior.can_catch()
+                && caught_by.is_none()
+                && game.tick + step >= state.grace_until
+                && vehicle.collides_with(&player.ship)
+            {
+                caught_by = Some(vehicle.class);
             }
         }
     }
 
-    if let Some(bribe) = bribe {
-        if bribe == game.credits {
-            msgs::add("You *barely* bribed the patrol.");
-        } else {
-            msgs::add(format!("You bribed the patrol, $-{}k", bribe));
+    if let Some(class) = caught_by {
+        if state.negotiation.is_none() {
+            state.negotiation = Some(Negotiation::new(
+                rng,
+                game,
+                class,
+                reputation.of(closest_planet),
+            ));
+            game.speed = GameSpeed::Paused;
+
+            sfx(
+                1,
+                SfxOptions {
+                    note: 1,
+                    octave: 1,
+                    duration: 5,
+                    channel: 0,
+                    ..Default::default()
+                },
+            );
         }
+    }
 
-        sfx(
-            1,
-            SfxOptions {
-                note: 1,
-                octave: 1,
-                duration: 5,
-                channel: 0,
-                ..Default::default()
-            },
-        );
+    let outcome = state
+        .negotiation
+        .as_mut()
+        .and_then(|negotiation| negotiation.tic(rng, game, state.wanted));
+
+    if let Some(outcome) = outcome {
+        state.negotiation = None;
+        game.speed = GameSpeed::Normal;
+
+        match outcome {
+            Outcome::Accepted { credits, slot } => {
+                if let Some(slot) = slot {
+                    game.cargo_hold.remove(slot);
+                    msgs::add("You handed over your cargo.");
+                } else if credits == game.credits {
+                    msgs::add("You *barely* bribed the patrol.");
+                } else {
+                    msgs::add(format!("You bribed the patrol, $-{}k", credits));
+                }
 
-        for vehicle in &mut state.vehicles {
-            if let PoliceVehicleBehavior::InPursuit = &vehicle.behavior {
-                vehicle.behavior = PoliceVehicleBehavior::escaping(rng);
+                sfx(
+                    1,
+                    SfxOptions {
+                        note: 1,
+                        octave: 1,
+                        duration: 5,
+                        channel: 0,
+                        ..Default::default()
+                    },
+                );
+
+                for vehicle in &mut state.vehicles {
+                    if vehicle.behavior.can_catch() {
+                        vehicle.behavior = PoliceVehicleBehavior::escaping(rng);
+                    }
+                }
+
+                game.credits -= credits;
+                state.dispatch_at = game.tick
+                    + rng.gen_range(
+                        30 * TICKS_PER_SECOND..60 * TICKS_PER_SECOND,
+                    );
+            }
+
+            Outcome::Refused => {
+                msgs::add("They refused your offer - run!");
+                state.increment_wanted_level(0.1);
+                state.grace_until = game.tick + GRACE_TICKS;
             }
-        }
 
-        game.credits -= bribe;
-        state.dispatch_at = game.time + rng.gen_range(30.0..60.0) * 1000.0;
+            Outcome::Fled => {
+                msgs::add("You slipped away!");
+                state.grace_until = game.tick + GRACE_TICKS;
+            }
+
+            Outcome::Caught => {
+                game_over = true;
+            }
+        }
     }
 
     // ---
@@ -228,14 +502,20 @@ pub fn tic(
     state
         .vehicles
         .extract_if(|vehicle| {
-            if let PoliceVehicleBehavior::Escaping { .. } = &vehicle.behavior {
-                vehicle.pos.x < -50000.0
-                    || vehicle.pos.y < -50000.0
-                    || vehicle.pos.x > 50000.0
-                    || vehicle.pos.y > 50000.0
-            } else {
-                false
-            }
+            let pos = vehicle.ship.pos;
+
+            let is_out_of_bounds = pos.x < -50000.0
+                || pos.y < -50000.0
+                || pos.x > 50000.0
+                || pos.y > 50000.0;
+
+            let has_escaped = is_out_of_bounds
+                && matches!(
+                    vehicle.behavior,
+                    PoliceVehicleBehavior::Escaping { .. }
+                );
+
+            has_escaped || vehicle.ship.can_be_towed_away(vehicle.fuel, camera)
         })
         .for_each(drop);
 
@@ -243,13 +523,15 @@ pub fn tic(
 
     let killed_vehicles: Vec<_> = state
         .vehicles
-        .extract_if(|vehicle| planets[0].collides_with(vehicle.pos))
+        .extract_if(|vehicle| vehicle.crashed_into.is_some())
         .collect();
 
-    if !killed_vehicles.is_empty() {
-        for vehicle in killed_vehicles {
+    if let Some(vehicle) = killed_vehicles.first() {
+        let fell_into_the_sun = vehicle.crashed_into == Some(0);
+
+        for vehicle in &killed_vehicles {
             for _ in 0..8 {
-                let pos = vehicle.pos
+                let pos = vehicle.ship.pos
                     + vec2(
                         rng.gen_range(-4.0..=4.0),
                         rng.gen_range(-4.0..=4.0),
@@ -265,11 +547,20 @@ pub fn tic(
         }
 
         msgs::add({
-            let msgs = [
-                "Burn, baby!",
-                "That must've hurt, sunshine!",
-                "Say hello to Icarus!",
-            ];
+            let msgs: &[&str] = if fell_into_the_sun {
+                &[
+                    "Burn, baby!",
+                    "That must've hurt, sunshine!",
+                    "Say hello to Icarus!",
+                ]
+            } else {
+                &[
+                    "Splat!",
+                    "Mind the planet, officer!",
+                    "That's one way to land.",
+                    "Gravity always wins.",
+                ]
+            };
 
             *msgs.choose(rng).unwrap()
         });
@@ -290,28 +581,121 @@ pub fn tic(
 }
 
 struct PoliceVehicle {
-    pub pos: Vec2,
+    pub class: PoliceClass,
+    pub ship: Ship,
+    pub fuel: f32,
+    pub smart: bool,
     pub behavior: PoliceVehicleBehavior,
+    pub crashed_into: Option<usize>,
 }
 
 impl PoliceVehicle {
-    fn rand(rng: &mut dyn RngCore) -> Self {
+    fn rand(rng: &mut dyn RngCore, game: &Game, wanted: f32) -> Self {
+        let mut class = PoliceClass::rand(rng, wanted);
+
+        // Blockades wait for the player where they're heading to
+        let blockaded_planet = game
+            .cargo_hold
+            .iter()
+            .map(|contract| contract.dst_planet)
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .copied();
+
+        let behavior = match (class, blockaded_planet) {
+            (PoliceClass::Blockade, Some(planet)) => {
+                PoliceVehicleBehavior::Blockading {
+                    planet,
+                    angle: rng.gen_range(0.0..(2.0 * PI)),
+                }
+            }
+
+            (PoliceClass::Blockade, None) => {
+                class = PoliceClass::Cruiser;
+                PoliceVehicleBehavior::InPursuit
+            }
+
+            _ => PoliceVehicleBehavior::InPursuit,
+        };
+
         Self {
-            pos: vec2(
-                rng.gen_range(-10000.0..10000.0),
-                rng.gen_range(-10000.0..10000.0),
-            ),
-            behavior: PoliceVehicleBehavior::InPursuit,
+            class,
+            ship: Ship {
+                pos: vec2(
+                    rng.gen_range(-10000.0..10000.0),
+                    rng.gen_range(-10000.0..10000.0),
+                ),
+                vel: Vec2::ZERO,
+            },
+            fuel: class.fuel(),
+            smart: rng.gen_bool(game.difficulty.smart_pursuers()),
+            behavior,
+            crashed_into: None,
+        }
+    }
+
+    fn dir_to(&self, pos: Vec2) -> Vec2 {
+        (pos - self.ship.pos).try_normalize().unwrap_or(Vec2::Y)
+    }
+
+    fn thrust(&mut self, target_vel: Vec2) -> Vec2 {
+        self.ship
+            .thrust(&mut self.fuel, target_vel, self.class.thrust())
+    }
+
+    // Planets block the line of sight
+    fn sees(&self, player: &Ship, planets: &[Planet], range: f32) -> bool {
+        if self.ship.pos.distance(player.pos) > range {
+            return false;
         }
+
+        planets.iter().all(|planet| {
+            distance_to_segment(
+                self.ship.pos - planet.pos,
+                player.pos - planet.pos,
+            ) > planet.radius
+        })
     }
 
     fn collides_with(&self, player: &Ship) -> bool {
-        self.pos.distance(player.pos) <= 90.0
+        self.ship.pos.distance(player.pos) <= self.class.capture_radius()
     }
 }
 
+// Assumes the player keeps flying in a straight line
+fn naive_intercept(vehicle: &Ship, player: &Ship, speed: f32) -> Vec2 {
+    let time_to_impact = vehicle.pos.distance(player.pos) / speed;
+
+    player.pos + player.vel * time_to_impact
+}
+
+// Returns the earliest point of the player's predicted trajectory that the
+// vehicle can reach in time
+fn smart_intercept(
+    game: &Game,
+    vehicle: &Ship,
+    player_path: &[TrajectoryStep],
+    speed: f32,
+) -> Option<Vec2> {
+    player_path
+        .iter()
+        .find(|step| {
+            let time = ticks_to_time(step.tick - game.tick);
+
+            vehicle.pos.distance(step.pos) <= speed * time
+        })
+        .map(|step| step.pos)
+}
+
 enum PoliceVehicleBehavior {
     InPursuit,
+
+    Searching { last_known_pos: Vec2, until: u32 },
+
+    Patrolling { waypoint: Vec2 },
+
+    Blockading { planet: usize, angle: f32 },
+
     Escaping { dir: Vec2 },
 }
 
@@ -329,7 +713,169 @@ impl PoliceVehicleBehavior {
         PoliceVehicleBehavior::Escaping { dir }
     }
 
+    fn patrolling(rng: &mut dyn RngCore, planets: &[Planet]) -> Self {
+        let planet = planets.choose(rng).unwrap();
+        let angle = rng.gen_range(0.0..(2.0 * PI));
+
+        PoliceVehicleBehavior::Patrolling {
+            waypoint: planet.pos
+                + Vec2::from_angle(angle) * (planet.radius + PATROL_DISTANCE),
+        }
+    }
+
     fn is_in_pursuit(&self) -> bool {
         matches!(self, PoliceVehicleBehavior::InPursuit)
     }
+
+    fn is_on_duty(&self) -> bool {
+        !matches!(self, PoliceVehicleBehavior::Escaping { .. })
+    }
+
+    fn can_catch(&self) -> bool {
+        matches!(
+            self,
+            PoliceVehicleBehavior::InPursuit
+                | PoliceVehicleBehavior::Blockading { .. }
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PoliceClass {
+    // Fast & light, but cheap to bribe
+    Interceptor,
+
+    // Slow & heavy, but catches the player from further away
+    Cruiser,
+
+    // Parks at the contract destinations
+    Blockade,
+}
+
+impl PoliceClass {
+    // The higher the wanted level, the heavier the vehicles
+    fn rand(rng: &mut dyn RngCore, wanted: f32) -> Self {
+        let weights = if wanted < 0.4 {
+            [7, 3, 0]
+        } else if wanted < 0.8 {
+            [5, 4, 1]
+        } else {
+            [3, 4, 3]
+        };
+
+        let classes = [
+            PoliceClass::Interceptor,
+            PoliceClass::Cruiser,
+            PoliceClass::Blockade,
+        ];
+
+        let mut n = rng.gen_range(0..weights.iter().sum::<u32>());
+
+        for (class, weight) in classes.into_iter().zip(weights) {
+            if n < weight {
+                return class;
+            }
+
+            n -= weight;
+        }
+
+        unreachable!()
+    }
+
+    fn speed(self, tick: u32) -> f32 {
+        let (min, max) = match self {
+            PoliceClass::Interceptor => (0.3, 1.0),
+            PoliceClass::Cruiser => (0.15, 0.5),
+            PoliceClass::Blockade => (0.1, 0.4),
+        };
+
+        if tick > MAX_SPEED_TICK {
+            max
+        } else {
+            remap(tick as f32, (0.0, MAX_SPEED_TICK as f32), (min, max))
+        }
+    }
+
+    // Enough to fight the sun from afar, but not close to it or a heavy planet
+    fn thrust(self) -> f32 {
+        match self {
+            PoliceClass::Interceptor => 0.006,
+            PoliceClass::Cruiser => 0.003,
+            PoliceClass::Blockade => 0.004,
+        }
+    }
+
+    // Total delta-v the vehicle can spend
+    fn fuel(self) -> f32 {
+        match self {
+            PoliceClass::Interceptor => 80.0,
+            PoliceClass::Cruiser => 150.0,
+            PoliceClass::Blockade => 200.0,
+        }
+    }
+
+    fn capture_radius(self) -> f32 {
+        match self {
+            PoliceClass::Interceptor => 90.0,
+            PoliceClass::Cruiser => 250.0,
+            PoliceClass::Blockade => 200.0,
+        }
+    }
+
+    // In thousands of credits
+    pub fn bribe(self) -> Range<u32> {
+        match self {
+            PoliceClass::Interceptor => 1..10,
+            PoliceClass::Cruiser => 10..30,
+            PoliceClass::Blockade => 20..40,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    // Police flies towards where the player would be flying straight
+    Easy,
+
+    // Some pursuers follow the player's predicted trajectory
+    #[default]
+    Normal,
+
+    // All pursuers follow the player's predicted trajectory
+    Hard,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Hard,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    // Probability of a newly dispatched vehicle being smart
+    fn smart_pursuers(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.0,
+            Difficulty::Normal => 0.5,
+            Difficulty::Hard => 1.0,
+        }
+    }
 }
@@ -0,0 +1,64 @@
+use crate::prelude::*;
+
+// Below it, planets don't offer the player any contracts
+pub const HOSTILE_LEVEL: f32 = -0.5;
+
+// Goes up with deliveries and down with failed or confiscated cargo;
+// -1.0..=1.0
+pub struct Reputation {
+    levels: Vec<f32>,
+}
+
+impl Reputation {
+    pub fn of(&self, planet: usize) -> f32 {
+        self.levels.get(planet).copied().unwrap_or(0.0)
+    }
+
+    pub fn change(&mut self, planet: usize, delta: f32) {
+        if self.levels.len() <= planet {
+            self.levels.resize(planet + 1, 0.0);
+        }
+
+        self.levels[planet] = (self.levels[planet] + delta).clamp(-1.0, 1.0);
+    }
+
+    pub fn reward_factor(&self, planet: usize) -> f32 {
+        1.0 + 0.25 * self.of(planet)
+    }
+
+    // Police is sent more often, and in greater numbers, where the player
+    // isn't welcome
+    pub fn police_factor(&self, planet: usize) -> f32 {
+        1.0 - 0.5 * self.of(planet)
+    }
+
+    pub fn is_hostile(&self, planet: usize) -> bool {
+        self.of(planet) < HOSTILE_LEVEL
+    }
+
+    pub fn label(&self, planet: usize) -> (&'static str, i32) {
+        let level = self.of(planet);
+
+        if level < HOSTILE_LEVEL {
+            ("Hostile", 2)
+        } else if level < -0.15 {
+            ("Unfriendly", 3)
+        } else if level < 0.15 {
+            ("Neutral", 13)
+        } else if level < 0.5 {
+            ("Friendly", 5)
+        } else {
+            ("Allied", 6)
+        }
+    }
+}
+
+static mut REPUTATION: Reputation = Reputation { levels: Vec::new() };
+
+pub unsafe fn get() -> &'static Reputation {
+    &REPUTATION
+}
+
+pub unsafe fn get_mut() -> &'static mut Reputation {
+    &mut REPUTATION
+}
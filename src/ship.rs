@@ -1,3 +1,4 @@
+use crate::police::PoliceClass;
 use crate::prelude::*;
 
 #[derive(Clone, Copy, Default)]
@@ -6,6 +7,31 @@ pub struct Ship {
     pub vel: Vec2,
 }
 
+impl Ship {
+    // Used by AI ships, which - unlike the player - burn fuel continuously;
+    // returns the acceleration applied
+    pub fn thrust(
+        &mut self,
+        fuel: &mut f32,
+        target_vel: Vec2,
+        max_acc: f32,
+    ) -> Vec2 {
+        let acc = ((target_vel - self.vel) / DT)
+            .clamp_length_max(max_acc.min(*fuel / DT));
+
+        self.vel += acc * DT;
+        *fuel = (*fuel - acc.length() * DT).max(0.0);
+
+        acc
+    }
+
+    // Dry ships would drift forever, so they get quietly towed away once
+    // nobody's looking
+    pub fn can_be_towed_away(&self, fuel: f32, camera: &Camera) -> bool {
+        fuel <= 0.0 && !camera.contains(camera.world_to_screen(self.pos))
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct ShipSprite {
     ty: ShipSpriteTy,
@@ -30,8 +56,12 @@ impl ShipSprite {
         Self::new(ShipSpriteTy::Player)
     }
 
-    pub fn police(in_pursuit: bool) -> Self {
-        Self::new(ShipSpriteTy::Police { in_pursuit })
+    pub fn hunter() -> Self {
+        Self::new(ShipSpriteTy::Hunter)
+    }
+
+    pub fn police(class: PoliceClass, in_pursuit: bool) -> Self {
+        Self::new(ShipSpriteTy::Police { class, in_pursuit })
     }
 
     pub fn at(mut self, at: Vec2) -> Self {
@@ -65,16 +95,27 @@ impl ShipSprite {
 
         let sprite = match id {
             ShipSpriteTy::Player => uvec2(16, 16),
+            ShipSpriteTy::Hunter => uvec2(12, 28),
+
+            ShipSpriteTy::Police { class, in_pursuit } => {
+                // Siren-on & siren-off frames, followed by the idle frame
+                // (except for interceptors, which have it elsewhere)
+                let row = match class {
+                    PoliceClass::Interceptor => uvec2(18, 16),
+                    PoliceClass::Cruiser => uvec2(0, 28),
+                    PoliceClass::Blockade => uvec2(6, 28),
+                };
 
-            ShipSpriteTy::Police { in_pursuit } => {
                 if in_pursuit {
                     if police_alternate_sprite() {
-                        uvec2(18, 16)
+                        row
                     } else {
-                        uvec2(20, 16)
+                        row + uvec2(2, 0)
                     }
-                } else {
+                } else if class == PoliceClass::Interceptor {
                     uvec2(16, 24)
+                } else {
+                    row + uvec2(4, 0)
                 }
             }
         };
@@ -88,7 +129,7 @@ impl ShipSprite {
         let engine_at = rotate(at + vec2(0.0, 16.0) * scale, at, rot);
 
         if engine {
-            let time = game.map(|game| game.time).unwrap_or_else(time);
+            let time = game.map(|game| game.time()).unwrap_or_else(time);
 
             let sprite_idx = if (time / 100.0) as i32 % 2 == 0 {
                 288
@@ -114,5 +155,9 @@ pub fn police_alternate_sprite() -> bool {
 #[derive(Clone, Copy)]
 enum ShipSpriteTy {
     Player,
-    Police { in_pursuit: bool },
+    Hunter,
+    Police {
+        class: PoliceClass,
+        in_pursuit: bool,
+    },
 }
@@ -1,99 +1,551 @@
-use std::iter;
+use std::{iter, mem};
 
-use crate::contracts::MIN_ACCEPT_DISTANCE;
+use crate::contracts::{MIN_ACCEPT_DISTANCE, MIN_DELIVERY_DISTANCE};
+use crate::death;
+use crate::manouvers::{self, ManouverNode};
 use crate::prelude::*;
 
-pub fn tic(game: &Game, player: &mut Player, planets: &mut [Planet]) {
+pub fn tic(game: &mut Game, player: &mut Player, planets: &mut [Planet]) {
+    let positions = FramePositions::new(game, planets);
+
     for step in 0..game.steps() {
-        eval(game.time + (step as f32) * DT, &mut player.ship, planets);
+        let tick = game.tick + step;
+
+        manouvers::execute(game, &mut player.ship, tick);
+
+        if let Some(planet_id) = player.landed_on {
+            if death::is_taking_off(&player.ship, planets, planet_id, tick) {
+                player.landed_on = None;
+            }
+        }
+
+        eval_ship(
+            game.integrator,
+            &mut player.ship,
+            planets,
+            positions.at(tick),
+            positions.at(tick + 1),
+        );
+
+        for (planet, &pos) in planets.iter_mut().zip(positions.at(tick + 1)) {
+            planet.pos = pos;
+        }
+
+        if let Some(planet_id) = player.landed_on {
+            death::hold_on_surface(
+                &mut player.ship,
+                planets,
+                planet_id,
+                tick + 1,
+            );
+        }
+    }
+
+    // Drop out of warp right before a planned manouver
+    if game.speed.is_warp()
+        && game
+            .manouver_nodes
+            .first()
+            .is_some_and(|node| node.tick < game.tick + 2 * game.steps())
+    {
+        game.drop_warp();
+    }
+
+    // Drop out of warp when we're about to enter some planet's vicinity, so
+    // that contracts & deliveries don't get skipped
+    if game.speed.is_warp() {
+        // Ship & planets are now at the end of this frame's steps; warp gets
+        // dropped only at the beginning of the next frame, so both are
+        // extrapolated over one more frame of the same length
+        let steps = game.steps();
+        let curr_tick = game.tick + steps;
+        let next_tick = curr_tick + steps;
+
+        let next_pos = player.ship.pos
+            + player.ship.vel * DT * (next_tick - curr_tick) as f32;
+
+        let approaches_any_planet =
+            planets.iter().enumerate().any(|(planet_id, planet)| {
+                let zone = planet.radius
+                    + MIN_ACCEPT_DISTANCE.max(MIN_DELIVERY_DISTANCE);
+
+                let curr = player.ship.pos - planet.pos;
+
+                let next = next_pos
+                    - planets::position_at(planets, planet_id, next_tick);
+
+                curr.length() > zone && distance_to_segment(curr, next) <= zone
+            });
+
+        if approaches_any_planet {
+            game.drop_warp();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Integrator {
+    // Cheap, but orbits slowly drift over time
+    Euler,
+
+    // Symplectic, so orbits keep their energy over long horizons
+    #[default]
+    Verlet,
+}
+
+impl Integrator {
+    pub fn next(self) -> Self {
+        match self {
+            Integrator::Euler => Integrator::Verlet,
+            Integrator::Verlet => Integrator::Euler,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Euler => "Euler",
+            Integrator::Verlet => "Verlet",
+        }
     }
 }
 
 #[derive(Clone, Copy)]
 pub struct TrajectoryStep {
+    pub tick: u32,
     pub pos: Vec2,
     pub color: u8,
-    pub touches: bool,
+    pub touches: Option<Touch>,
+
+    // Tick of the manouver node executed at this step
+    pub node: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Touch {
+    // Close enough to accept / deliver contracts
+    Approaching,
+
+    Impact,
+}
+
+// Computed once per frame and shared by all the ships simulated in it
+pub struct FramePositions {
+    tick: u32,
+    positions: Vec<Vec<Vec2>>,
+}
+
+impl FramePositions {
+    pub fn new(game: &Game, planets: &[Planet]) -> Self {
+        let positions = (0..=game.steps())
+            .map(|step| {
+                let mut positions = Vec::new();
+
+                planets::positions_at(
+                    planets,
+                    game.tick + step,
+                    &mut positions,
+                );
+                positions
+            })
+            .collect();
+
+        Self {
+            tick: game.tick,
+            positions,
+        }
+    }
+
+    pub fn at(&self, tick: u32) -> &[Vec2] {
+        &self.positions[(tick - self.tick) as usize]
+    }
 }
 
-pub fn trajectory(
+pub const TRAJECTORY_STEPS: u32 = 650;
+
+pub fn trajectory<'a>(
     game: &Game,
     player: &Ship,
-    planets: &[Planet],
-) -> impl Iterator<Item = TrajectoryStep> {
-    let mut step = 0;
-    let mut time = game.time;
-    let mut player = *player;
-    let mut planets = planets.to_vec();
+    planets: &'a [Planet],
+    nodes: &[ManouverNode],
+) -> impl Iterator<Item = TrajectoryStep> + 'a {
+    let mut trajectory = Trajectory::new(game, player, nodes, TRAJECTORY_STEPS);
 
-    let mut prev_pos = vec2(f32::MAX, f32::MAX);
+    iter::from_fn(move || trajectory.next(planets))
+}
 
-    iter::from_fn(move || {
-        loop {
-            step += 1;
-            time += DT;
+// Can be suspended & resumed at any step, spreading the work across frames
+pub struct Trajectory {
+    integrator: Integrator,
+    max_steps: u32,
+    step: u32,
+    tick: u32,
+    ship: Ship,
+    nodes: Vec<ManouverNode>,
+    prev_pos: Vec2,
+    crashed: bool,
+
+    // Planet positions at the current & next tick
+    positions: Vec<Vec2>,
+    next_positions: Vec<Vec2>,
+}
+
+impl Trajectory {
+    pub fn new(
+        game: &Game,
+        ship: &Ship,
+        nodes: &[ManouverNode],
+        max_steps: u32,
+    ) -> Self {
+        Self {
+            integrator: game.integrator,
+            max_steps,
+            step: 0,
+            tick: game.tick,
+            ship: *ship,
+            nodes: nodes.to_vec(),
+            prev_pos: vec2(f32::MAX, f32::MAX),
+            crashed: false,
+            positions: Vec::new(),
+            next_positions: Vec::new(),
+        }
+    }
+
+    pub fn starting_at(mut self, tick: u32) -> Self {
+        self.tick = tick;
+        self.positions.clear();
+        self
+    }
+
+    pub fn steps(&self) -> u32 {
+        self.step
+    }
+
+    pub fn until(&mut self, tick: u32) {
+        self.max_steps = self.step + tick.saturating_sub(self.tick);
+    }
+
+    pub fn next(&mut self, planets: &[Planet]) -> Option<TrajectoryStep> {
+        if self.crashed {
+            return None;
+        }
 
-            if step > 650 {
+        let mut node = None;
+
+        loop {
+            if self.step >= self.max_steps {
                 return None;
             }
 
-            sim::eval(time, &mut player, &mut planets);
+            node = self.advance(planets).or(node);
 
-            if player.pos.distance(prev_pos) >= 100.0 {
+            if self.crashed
+                || node.is_some()
+                || self.ship.pos.distance(self.prev_pos) >= 100.0
+            {
                 break;
             }
         }
 
         let mut closest_color = 12;
         let mut closest_dist = f32::MAX;
-        let mut touches = false;
 
-        for planet in &planets {
-            let dist = planet.pos.distance(player.pos);
+        let mut touches = if self.crashed {
+            Some(Touch::Impact)
+        } else {
+            None
+        };
+
+        for (planet, planet_pos) in planets.iter().zip(&self.positions) {
+            let dist = planet_pos.distance(self.ship.pos);
 
             if dist < closest_dist {
                 closest_color = planet.color;
                 closest_dist = dist;
             }
 
-            if dist <= planet.radius + MIN_ACCEPT_DISTANCE {
-                touches = true;
-                break;
+            if touches.is_none() && dist <= planet.radius + MIN_ACCEPT_DISTANCE
+            {
+                touches = Some(Touch::Approaching);
             }
         }
 
-        prev_pos = player.pos;
+        self.prev_pos = self.ship.pos;
 
         Some(TrajectoryStep {
-            pos: player.pos,
+            tick: self.tick,
+            pos: self.ship.pos,
             color: closest_color,
             touches,
+            node,
         })
-    })
+    }
+
+    // Simulates a single tick, the same way sim::tic() does
+    fn advance(&mut self, planets: &[Planet]) -> Option<u32> {
+        let mut node = None;
+
+        for planned in &self.nodes {
+            if planned.tick == self.tick {
+                self.ship.vel += planned.dv;
+                node = Some(self.tick);
+            }
+        }
+
+        if self.positions.is_empty() {
+            planets::positions_at(planets, self.tick, &mut self.positions);
+        }
+
+        planets::positions_at(planets, self.tick + 1, &mut self.next_positions);
+
+        sim::eval_ship(
+            self.integrator,
+            &mut self.ship,
+            planets,
+            &self.positions,
+            &self.next_positions,
+        );
+
+        self.step += 1;
+        self.tick += 1;
+
+        mem::swap(&mut self.positions, &mut self.next_positions);
+
+        self.crashed =
+            planets::collision_at(planets, &self.positions, self.ship.pos)
+                .is_some();
+
+        node
+    }
 }
 
-pub fn eval(time: f32, player: &mut Ship, planets: &mut [Planet]) {
-    for planet_id in 0..planets.len() {
-        let parent_pos = planets[planet_id]
-            .parent
-            .map(|parent_id| planets[parent_id].pos)
-            .unwrap_or_default();
+// Recomputed only when its inputs change - as long as the ship follows the
+// prediction, only the new tail gets simulated
+pub struct TrajectoryCache {
+    key: Option<TrajectoryKey>,
+
+    // Replays the prediction, to tell whether the ship is still on it
+    follower: Option<Trajectory>,
+
+    trajectory: Option<Trajectory>,
+    steps: Vec<TrajectoryStep>,
+}
+
+#[derive(PartialEq)]
+struct TrajectoryKey {
+    integrator: Integrator,
+    nodes: Vec<(u32, Vec2)>,
+}
+
+impl TrajectoryCache {
+    const MAX_STEPS: u32 = 4 * TRAJECTORY_STEPS;
+    const STEPS_PER_FRAME: u32 = TRAJECTORY_STEPS;
+
+    pub const fn new() -> Self {
+        Self {
+            key: None,
+            follower: None,
+            trajectory: None,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        game: &Game,
+        ship: &Ship,
+        planets: &[Planet],
+        nodes: &[ManouverNode],
+    ) -> &[TrajectoryStep] {
+        // Nodes that have already been executed don't matter anymore
+        let key = TrajectoryKey {
+            integrator: game.integrator,
+            nodes: nodes
+                .iter()
+                .filter(|node| node.tick >= game.tick)
+                .map(|node| (node.tick, node.dv))
+                .collect(),
+        };
+
+        if let Some(cached) = &mut self.key {
+            cached.nodes.retain(|&(tick, _)| tick >= game.tick);
+        }
+
+        if self.key.as_ref() != Some(&key) || !self.follow(game, ship, planets)
+        {
+            self.key = Some(key);
+            self.steps.clear();
+
+            self.follower = Some(Trajectory::new(game, ship, nodes, 0));
+            self.trajectory = Some(Trajectory::new(game, ship, nodes, 0));
+        }
+
+        if let Some(trajectory) = &mut self.trajectory {
+            trajectory.until(game.tick + Self::MAX_STEPS);
+
+            let max_steps = trajectory.steps() + Self::STEPS_PER_FRAME;
+
+            while trajectory.steps() < max_steps {
+                let Some(step) = trajectory.next(planets) else {
+                    break;
+                };
+
+                self.steps.push(step);
+            }
+        }
+
+        &self.steps
+    }
+
+    // Returns false if the ship went off the prediction
+    fn follow(&mut self, game: &Game, ship: &Ship, planets: &[Planet]) -> bool {
+        let Some(follower) = &mut self.follower else {
+            return false;
+        };
+
+        // Catching up from too far back would cost more than starting over
+        if follower.tick > game.tick
+            || game.tick - follower.tick > Self::MAX_STEPS
+        {
+            return false;
+        }
+
+        while follower.tick < game.tick {
+            follower.advance(planets);
+        }
+
+        if follower.ship.pos != ship.pos || follower.ship.vel != ship.vel {
+            return false;
+        }
+
+        self.steps.retain(|step| step.tick > game.tick);
+
+        true
+    }
+}
 
-        let planet = &mut planets[planet_id];
-        let orbit = PI * 2.0 * time / planet.orbit_speed + planet.orbit_phase;
-        let orbit = vec2(orbit.cos(), orbit.sin());
+// Planets are at `curr` at the beginning of the tick and `next` at its end
+pub fn eval_ship(
+    integrator: Integrator,
+    ship: &mut Ship,
+    planets: &[Planet],
+    curr: &[Vec2],
+    next: &[Vec2],
+) {
+    match integrator {
+        Integrator::Euler => {
+            ship.vel += gravity(ship.pos, planets, curr) * DT;
+            ship.pos += ship.vel * DT;
+        }
+
+        Integrator::Verlet => {
+            ship.vel += gravity(ship.pos, planets, curr) * DT * 0.5;
+            ship.pos += ship.vel * DT;
+            ship.vel += gravity(ship.pos, planets, next) * DT * 0.5;
+        }
+    }
+}
 
-        planet.pos = parent_pos + orbit * planet.orbit_radius;
+pub fn eval_planets(tick: u32, planets: &mut [Planet]) {
+    for planet_id in 0..planets.len() {
+        planets[planet_id].pos = planets::position_at(planets, planet_id, tick);
     }
+}
 
-    // ---
+pub fn gravity(pos: Vec2, planets: &[Planet], positions: &[Vec2]) -> Vec2 {
+    let mut acc = Vec2::ZERO;
 
-    for planet in planets.iter() {
-        let d = planet.pos - player.pos;
+    for (planet_id, planet) in planets.iter().enumerate() {
+        let d = positions[planet_id] - pos;
         let f = planet.mass / d.length_squared();
 
-        player.vel += f * d * DT;
+        acc += f * d;
+    }
+
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planets::galaxies;
+
+    // With 1/r gravity the potential is logarithmic
+    fn energy(ship: &Ship, sun: &Planet) -> f32 {
+        0.5 * ship.vel.length_squared()
+            + sun.mass * ship.pos.distance(sun.pos).ln()
+    }
+
+    // Returns the largest relative energy drift of an orbit around the sun
+    fn max_energy_drift(integrator: Integrator) -> f32 {
+        let planets = &galaxies::gamma()[..1];
+        let sun = &planets[0];
+
+        let mut ship = Ship {
+            pos: vec2(1500.0, 0.0),
+            vel: vec2(0.0, 0.8 * sun.mass.sqrt()),
+        };
+
+        let initial = energy(&ship, sun);
+        let mut drift: f32 = 0.0;
+
+        let positions = [sun.pos];
+
+        for _ in 0..5000 {
+            eval_ship(integrator, &mut ship, planets, &positions, &positions);
+
+            drift = drift.max(((energy(&ship, sun) - initial) / initial).abs());
+        }
+
+        drift
     }
 
-    player.pos += player.vel * DT;
+    // Flies the ship through the whole (moving) gamma system and then back
+    // again, with its velocity flipped - a symplectic integrator retraces its
+    // path, so returns how far from the start the ship ends up
+    fn reversal_error(integrator: Integrator) -> f32 {
+        let planets = &galaxies::gamma();
+        let start = vec2(4000.0, 0.0);
+
+        let mut ship = Ship {
+            pos: start,
+            vel: vec2(0.0, planets[0].mass.sqrt()),
+        };
+
+        let mut curr = Vec::new();
+        let mut next = Vec::new();
+
+        for tick in 0..3000 {
+            planets::positions_at(planets, tick, &mut curr);
+            planets::positions_at(planets, tick + 1, &mut next);
+            eval_ship(integrator, &mut ship, planets, &curr, &next);
+        }
+
+        ship.vel = -ship.vel;
+
+        for tick in (0..3000).rev() {
+            planets::positions_at(planets, tick + 1, &mut curr);
+            planets::positions_at(planets, tick, &mut next);
+            eval_ship(integrator, &mut ship, planets, &curr, &next);
+        }
+
+        ship.pos.distance(start)
+    }
+
+    #[test]
+    fn verlet_conserves_energy() {
+        let euler = max_energy_drift(Integrator::Euler);
+        let verlet = max_energy_drift(Integrator::Verlet);
+
+        assert!(verlet < 0.0001, "verlet drift = {}", verlet);
+        assert!(verlet < euler, "verlet = {}, euler = {}", verlet, euler);
+    }
+
+    #[test]
+    fn verlet_is_reversible_among_orbiting_planets() {
+        let euler = reversal_error(Integrator::Euler);
+        let verlet = reversal_error(Integrator::Verlet);
+
+        assert!(verlet < 1.0, "verlet error = {}", verlet);
+        assert!(verlet < euler, "verlet = {}, euler = {}", verlet, euler);
+    }
 }
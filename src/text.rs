@@ -4,6 +4,7 @@ pub struct Text {
     text: String,
     at: Vec2,
     color: i32,
+    small: bool,
     alignment: TextAlignment,
 }
 
@@ -13,6 +14,7 @@ impl Text {
             text: text.to_string(),
             at: Default::default(),
             color: 12,
+            small: false,
             alignment: Default::default(),
         }
     }
@@ -27,6 +29,11 @@ impl Text {
         self
     }
 
+    pub fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+
     pub fn align_center(mut self) -> Self {
         self.alignment = TextAlignment::Center;
         self
@@ -42,10 +49,17 @@ impl Text {
             text,
             at,
             color,
+            small,
             alignment,
         } = self;
 
-        let width = print_alloc(&text, 1024, 1024, Default::default());
+        let opts = PrintOptions {
+            color,
+            small_font: small,
+            ..Default::default()
+        };
+
+        let width = print_alloc(&text, 1024, 1024, opts);
 
         let at = match alignment {
             TextAlignment::Left => at,
@@ -53,11 +67,6 @@ impl Text {
             TextAlignment::Right => vec2(at.x - width as f32, at.y),
         };
 
-        let opts = PrintOptions {
-            color,
-            ..Default::default()
-        };
-
         print!(text, at.x as i32, at.y as i32, opts);
 
         width
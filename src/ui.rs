@@ -1,10 +1,29 @@
+use crate::contracts::LATE_DELIVERY_TICKS;
+use crate::countermeasures::{self, Countermeasure};
+use crate::game::{CARGO_HOLD_SLOTS, TICKS_PER_DAY};
+use crate::hunters::HunterState;
+use crate::orbit::Orbit;
 use crate::prelude::*;
+use crate::reputation::Reputation;
+use crate::{autopilot, fuel};
 
-pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
+static mut SPEED_KEYS_LOCKED: bool = false;
+
+pub fn tic(
+    game: &mut Game,
+    camera: &Camera,
+    player: &Player,
+    planets: &[Planet],
+    police: &police::PoliceState,
+    hunters: &HunterState,
+    reputation: &Reputation,
+) {
     let m = mouse();
     let mx = m.x as i32;
     let my = m.y as i32;
-    let visible = game.selected_contract.is_none() && !camera.is_animating();
+    let visible = game.selected_contract.is_none()
+        && !camera.is_animating()
+        && !police.is_negotiating();
 
     // -- Credits --
     if visible && !game.manouver_mode {
@@ -33,7 +52,7 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
 
             let sprite = if active {
                 if almost_inactive {
-                    if game.time % 1000.0 < 500.0 {
+                    if game.tick % TICKS_PER_SECOND < TICKS_PER_SECOND / 2 {
                         263
                     } else {
                         262
@@ -52,6 +71,32 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
         }
     }
 
+    // -- Detection --
+    if visible && !game.manouver_mode && police.is_on_duty() {
+        let (text, color) = if police.is_detected() {
+            ("DETECTED", 2)
+        } else {
+            ("HIDDEN", 5)
+        };
+
+        Text::new(text)
+            .at(vec2(WIDTH as f32 - 1.0, 23.0))
+            .align_right()
+            .color(color)
+            .small()
+            .draw();
+    }
+
+    // -- Bounty --
+    if visible && !game.manouver_mode && hunters.bounty() > 0 {
+        Text::new(format!("Bounty ${}k", hunters.bounty()))
+            .at(vec2(WIDTH as f32 - 1.0, 30.0))
+            .align_right()
+            .color(13)
+            .small()
+            .draw();
+    }
+
     // -- Day number --
     if visible && !game.manouver_mode {
         Text::new(format!("Day {}", game.day()))
@@ -79,6 +124,14 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
             && my >= HEIGHT - 16 - 4
             && my < HEIGHT - 16 - 4 + 16 * 2;
 
+        let warp_button_x = WIDTH - 16 * 3 - 22;
+        let warp_button_y = HEIGHT - 14;
+
+        let mouse_over_warp_button = mx >= warp_button_x
+            && mx < warp_button_x + 20
+            && my >= warp_button_y
+            && my < warp_button_y + 12;
+
         spr(
             if matches!(game.speed, GameSpeed::Paused) {
                 sprites::buttons::active::STOP
@@ -133,6 +186,31 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
             },
         );
 
+        let warp_color = if game.speed.is_warp() {
+            4
+        } else if mouse_over_warp_button {
+            12
+        } else {
+            14
+        };
+
+        let warp_steps = if game.speed.is_warp() {
+            game.steps()
+        } else {
+            GameSpeed::Warp4.steps()
+        };
+
+        rectb(warp_button_x, warp_button_y, 20, 12, warp_color);
+
+        Text::new(format!("x{}", warp_steps))
+            .at(vec2(
+                2.0 * (warp_button_x + 11) as f32,
+                HEIGHT as f32 - 11.0,
+            ))
+            .align_center()
+            .color(warp_color as i32)
+            .draw();
+
         if mouse_left_pressed() {
             if mouse_over_stop_button {
                 game.speed = GameSpeed::Paused;
@@ -140,6 +218,8 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
                 game.speed = GameSpeed::Normal;
             } else if mouse_over_fast_button {
                 game.speed = GameSpeed::Fast;
+            } else if mouse_over_warp_button {
+                game.speed = game.speed.next_warp();
             }
         }
     }
@@ -159,10 +239,15 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
 
         rect(2, fuel_y, 12, fuel_h, 6);
 
-        if game.manouver_mode {
+        let reserved_fuel_h =
+            (game.reserved_fuel().min(game.fuel) * fuel_height) as i32;
+
+        rect(2, fuel_y, 12, reserved_fuel_h, 3);
+
+        if game.manouver_mode || game.suggested_dv().is_some() {
             let fuel_cost_h = (game.manouver_fuel * fuel_height) as i32;
 
-            rect(2, fuel_y, 12, fuel_cost_h, 2);
+            rect(2, fuel_y + reserved_fuel_h, 12, fuel_cost_h, 2);
         }
 
         spr(
@@ -176,6 +261,17 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
                 ..Default::default()
             },
         );
+
+        // Cargo weighs the ship down, making each burn cost more fuel
+        let cargo_mass = game.cargo_mass();
+
+        if cargo_mass > 0.0 {
+            Text::new(format!("{:.0}t", cargo_mass))
+                .at(vec2(2.0, (HEIGHT - 16 * 3 - 7) as f32))
+                .color(13)
+                .small()
+                .draw();
+        }
     }
 
     // -- Cargo hold --
@@ -183,7 +279,7 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
     let left_offset = 24.0;
     let bottom_offset = 8.0;
 
-    for (idx, cargo) in game.cargo_hold.iter().enumerate() {
+    for idx in 0..CARGO_HOLD_SLOTS {
         let hold_cell_offset = 16.0 * idx as f32;
 
         Img::sprite_idx_with_size(sprites::CARGO_HOLD as u32, uvec2(2, 2))
@@ -192,25 +288,207 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
                 HEIGHT as f32 - bottom_offset - hold_cell_offset,
             ))
             .draw();
+    }
+
+    let mut first_slot = 0;
+
+    for contract in &game.cargo_hold {
+        let size = contract.cargo.ty().size;
+        let hold_cell_offset = 16.0 * first_slot as f32;
 
-        if let Some(contract) = cargo {
-            Img::sprite_idx_with_size(contract.cargo.sprite(), uvec2(2, 2))
+        // Bulky cargo fills all of its slots
+        for slot in first_slot..first_slot + size {
+            Img::sprite_idx_with_size(contract.cargo.ty().sprite, uvec2(2, 2))
                 .at(vec2(
                     left_offset,
+                    HEIGHT as f32 - bottom_offset - 16.0 * slot as f32,
+                ))
+                .draw();
+        }
+
+        first_slot += size;
+
+        // Delivery deadline
+        let (deadline, color) = if game.tick <= contract.deliver_by {
+            (contract.deliver_by, 12)
+        } else {
+            (contract.deliver_by + LATE_DELIVERY_TICKS, 3)
+        };
+
+        let days = deadline.saturating_sub(game.tick) / TICKS_PER_DAY;
+
+        if days > 0 || blink() {
+            Text::new(format!("{}d", days))
+                .at(vec2(
+                    left_offset - 7.0,
+                    HEIGHT as f32 - bottom_offset - hold_cell_offset + 2.0,
+                ))
+                .color(color)
+                .small()
+                .draw();
+        }
+
+        // Destination can't be reached with the remaining fuel
+        if fuel::is_reachable(contract.dst_planet) == Some(false) {
+            Text::new("!")
+                .at(vec2(
+                    left_offset + 12.0,
                     HEIGHT as f32 - bottom_offset - hold_cell_offset,
                 ))
+                .color(2)
                 .draw();
         }
     }
 
-    // -- Keyboard controls --
+    // Autopilot hint, shown next to the hovered cargo
+    if visible
+        && !game.manouver_mode
+        && game.autopilot.is_none()
+        && autopilot::hovered_cargo(game).is_some()
+    {
+        let slot = ((HEIGHT as f32 - mouse_pos().y) / 16.0) as usize;
+
+        let text = if game.is_paused() {
+            "Click for autopilot"
+        } else {
+            "Pause, then click for autopilot"
+        };
+
+        Text::new(text)
+            .at(vec2(50.0, HEIGHT as f32 - 16.0 * (slot + 1) as f32 + 5.0))
+            .small()
+            .draw();
+    }
+
+    // -- Countermeasures --
+    for countermeasure in Countermeasure::ALL {
+        let pos = countermeasures::slot_pos(countermeasure);
+        let center = pos + vec2(8.0, 8.0);
+        let charges = countermeasure.charges(game);
+        let color = if charges > 0 { 12 } else { 14 };
+
+        Img::sprite_idx_with_size(sprites::CARGO_HOLD as u32, uvec2(2, 2))
+            .at(center)
+            .draw();
+
+        match countermeasure {
+            Countermeasure::Decoy => {
+                trib(
+                    center.x,
+                    center.y - 4.0,
+                    center.x - 3.0,
+                    center.y + 3.0,
+                    center.x + 3.0,
+                    center.y + 3.0,
+                    if charges > 0 { 5 } else { 14 },
+                );
+            }
+
+            Countermeasure::Jammer => {
+                let color = if charges > 0 { 10 } else { 14 };
+
+                circb(center.x as i32, center.y as i32, 1, color);
+                circb(center.x as i32, center.y as i32, 4, color);
+            }
+        }
+
+        Text::new(charges)
+            .at(pos + vec2(11.0, 10.0))
+            .color(color)
+            .small()
+            .draw();
+
+        if visible && countermeasures::is_hovered(countermeasure) {
+            let (_, hotkey) = countermeasure.hotkey();
+
+            Text::new(format!(
+                "{} [{}], sold at fuel planets",
+                countermeasure.name(),
+                hotkey
+            ))
+            .at(pos + vec2(18.0, 5.0))
+            .small()
+            .draw();
+        }
+    }
+
+    // -- Planet tooltip --
     if visible && !game.manouver_mode {
+        let mpos = mouse_pos();
+
+        let hovered = planets.iter().enumerate().skip(1).find(|(_, planet)| {
+            let radius = (camera.scale * planet.radius).max(2.0);
+
+            camera.world_to_screen(planet.pos).distance(mpos) < radius + 4.0
+        });
+
+        if let Some((planet_id, planet)) = hovered {
+            let (label, color) = reputation.label(planet_id);
+            let pos = camera.world_to_screen(planet.pos);
+
+            let mut lines = vec![(
+                format!(
+                    "{} ({:+.0}%)",
+                    label,
+                    100.0 * reputation.of(planet_id)
+                ),
+                color,
+            )];
+
+            if planet.customs {
+                lines.push(("Customs".to_string(), 13));
+            }
+
+            if planet.guild {
+                lines.push(("Hunters guild".to_string(), 13));
+            }
+
+            for (idx, (text, color)) in lines.into_iter().enumerate() {
+                Text::new(text)
+                    .at(pos + vec2(8.0, -4.0 + 7.0 * idx as f32))
+                    .color(color)
+                    .small()
+                    .draw();
+            }
+        }
+    }
+
+    // -- Keyboard controls --
+    let speed_keys = [
+        keys::DIGIT_1,
+        keys::DIGIT_2,
+        keys::DIGIT_3,
+        keys::DIGIT_4,
+        keys::DIGIT_5,
+        keys::DIGIT_6,
+        keys::DIGIT_7,
+    ];
+
+    // Bribe dialog uses the same keys, so they're ignored until released -
+    // otherwise picking an option would change the speed as well
+    let speed_keys_locked = unsafe { &mut SPEED_KEYS_LOCKED };
+
+    if police.is_negotiating() {
+        *speed_keys_locked = true;
+    } else if !speed_keys.into_iter().any(key) {
+        *speed_keys_locked = false;
+    }
+
+    if visible && !game.manouver_mode && !*speed_keys_locked {
         if key(keys::DIGIT_1) {
             game.speed = GameSpeed::Paused;
         } else if key(keys::DIGIT_2) {
             game.speed = GameSpeed::Normal;
         } else if key(keys::DIGIT_3) {
             game.speed = GameSpeed::Fast;
+        } else if key(keys::DIGIT_4) {
+            game.speed = GameSpeed::Warp4;
+        } else if key(keys::DIGIT_5) {
+            game.speed = GameSpeed::Warp8;
+        } else if key(keys::DIGIT_6) {
+            game.speed = GameSpeed::Warp16;
+        } else if key(keys::DIGIT_7) {
+            game.speed = GameSpeed::Warp32;
         } else if keyp(keys::SPACE, 16, 16) {
             game.speed = match game.speed {
                 GameSpeed::Paused => GameSpeed::Normal,
@@ -221,6 +499,12 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
 
     // ---
 
+    let shows_planner_help = visible
+        && !game.manouver_mode
+        && game.is_paused()
+        && game.manouver_planner
+        && game.autopilot.is_none();
+
     if visible && game.manouver_mode {
         if game.fuel == 0.0 {
             Text::new("You don't have fuel.")
@@ -236,5 +520,38 @@ pub fn tic(game: &mut Game, camera: &Camera, police: &police::PoliceState) {
                 .at(vec2(0.0, 8.0))
                 .draw();
         }
+    } else if shows_planner_help {
+        Text::new("Click the trajectory to plan a manouver.")
+            .at(vec2(0.0, 0.0))
+            .draw();
+
+        Text::new("Right-click a node to remove it.")
+            .at(vec2(0.0, 8.0))
+            .draw();
+
+        Text::new("Press N to close the planner.")
+            .at(vec2(0.0, 16.0))
+            .draw();
+    }
+
+    // -- Orbit info --
+    let is_over_ship = camera
+        .world_to_screen(player.ship.pos)
+        .distance(vec2(mx as f32, my as f32))
+        < 10.0;
+
+    let is_burning_at_ship = game.manouver_mode && game.manouver_node.is_none();
+
+    if visible && (is_over_ship || is_burning_at_ship) {
+        let mut ship = player.ship;
+
+        if is_burning_at_ship {
+            ship.vel += game.manouver_dv;
+        }
+
+        // Planner's help takes one line more than the other messages up there
+        let y = if shows_planner_help { 26.0 } else { 18.0 };
+
+        Orbit::new(game, &ship, planets).draw(vec2(0.0, y), planets);
     }
 }
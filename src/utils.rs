@@ -6,7 +6,10 @@ use crate::prelude::*;
 
 pub mod arrow;
 
-pub const DT: f32 = 1000.0 / 60.0;
+pub const TICKS_PER_SECOND: u32 = 60;
+
+// In milliseconds
+pub const DT: f32 = 1000.0 / (TICKS_PER_SECOND as f32);
 
 pub mod btns {
     pub const UP: i32 = 0;
@@ -55,6 +58,9 @@ pub mod keys {
     pub const DIGIT_9: i32 = 36;
 
     pub const SPACE: i32 = 48;
+    pub const RETURN: i32 = 50;
+    pub const LEFT: i32 = 60;
+    pub const RIGHT: i32 = 61;
 }
 
 pub mod sounds {
@@ -284,6 +290,22 @@ pub fn remap(value: f32, from: (f32, f32), to: (f32, f32)) -> f32 {
     (value - from_min) / (from_max - from_min) * (to_max - to_min) + to_min
 }
 
+pub fn distance_to_segment(a: Vec2, b: Vec2) -> f32 {
+    lerp(a, b, closest_on_segment(a, b)).length()
+}
+
+// Returns t (0..=1) of the point on segment a-b closest to origin
+pub fn closest_on_segment(a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len = ab.length_squared();
+
+    if len == 0.0 {
+        return 0.0;
+    }
+
+    (-a.dot(ab) / len).clamp(0.0, 1.0)
+}
+
 pub fn blink() -> bool {
     time() % 1000.0 < 500.0
 }